@@ -11,6 +11,7 @@ fn main() {
     if !output.status.success() || output.stdout.is_empty() {
         panic!("NO AVX_512");
     }
+    println!("cargo:rerun-if-changed=src/assembly");
     cc::Build::new()
         .file("src/assembly/innerproduct256.S")
         .file("src/assembly/modip256_mont.S")
@@ -18,5 +19,15 @@ fn main() {
         .file("src/assembly/modsum256.S")
         .file("src/assembly/modadd256.S")
         .file("src/assembly/modsub256.S")
+        .file("src/assembly/modmul256_nt.S")
+        .file("src/assembly/modadd256_nt.S")
+        .file("src/assembly/modsub256_nt.S")
+        .file("src/assembly/modvsum256.S")
+        .file("src/assembly/modlc256_mont.S")
+        .file("src/assembly/modadd384.S")
+        .file("src/assembly/modsub384.S")
+        .file("src/assembly/modsum384.S")
+        .file("src/assembly/modmul384_mont.S")
+        .file("src/assembly/modip384_mont.S")
         .compile("avx512montmul");
 }
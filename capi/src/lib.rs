@@ -0,0 +1,5 @@
+//! Links the C ABI of `vectorized-fields` into a shared and a static library. The exported
+//! functions and constants are those of `vectorized_fields::capi`, declared in
+//! `include/vectorized_fields.h`.
+
+pub use vectorized_fields::capi::*;
@@ -0,0 +1,43 @@
+//! Compiles `tests/capi_test.c` against the generated header and this package's shared library
+//! with the system C compiler (`$CC`, or `cc`), then runs it.
+
+use std::{env, path::PathBuf, process::Command};
+
+#[test]
+fn c_abi() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let exe = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("capi_test");
+
+    // Cargo builds the library, in all of its crate types, into the `deps/` directory next to
+    // this test before running it.
+    let lib_dir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+
+    let status = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror"])
+        .arg(manifest_dir.join("tests/capi_test.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("../include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lvectorized_fields_capi")
+        .arg("-o")
+        .arg(&exe)
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "compiling the C test failed");
+
+    // Cargo's `LD_LIBRARY_PATH` also lists the profile directory, which would take precedence
+    // over the rpath and may hold a library from an earlier `cargo build`.
+    let output = Command::new(&exe)
+        .env_remove("LD_LIBRARY_PATH")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{:?} {}{}",
+        output.status,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
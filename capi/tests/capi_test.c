@@ -0,0 +1,183 @@
+// Exercises the C ABI against small integers whose results are easy to check in plain C.
+
+#include <stdint.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "vectorized_fields.h"
+
+#define N 37
+#define K 3
+
+static const uint64_t MODULUS[4] = {
+    0x43e1f593f0000001, 0x2833e84879b97091, 0xb85045b68181585d, 0x30644e72e131a029,
+};
+
+static int failures = 0;
+
+#define CHECK(cond)                                                        \
+    do {                                                                   \
+        if (!(cond)) {                                                     \
+            printf("%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+            failures++;                                                    \
+        }                                                                  \
+    } while (0)
+
+// Montgomery form of the small integers v[0..len].
+static void from_u64(uint32_t mode, uint64_t *z, const uint64_t *v, size_t len) {
+    uint64_t canonical[N * 4];
+    memset(canonical, 0, sizeof canonical);
+    for (size_t i = 0; i < len; i++) {
+        canonical[4 * i] = v[i];
+    }
+    CHECK(vf_from_canonical(VF_FIELD_BN254_FR, mode, z, canonical, len) == VF_OK);
+}
+
+// Whether the Montgomery-form elements x[0..len] equal the small integers v[0..len].
+static int equals_u64(uint32_t mode, const uint64_t *x, const uint64_t *v, size_t len) {
+    uint64_t canonical[N * 4];
+    if (vf_to_canonical(VF_FIELD_BN254_FR, mode, canonical, x, len) != VF_OK) {
+        return 0;
+    }
+    for (size_t i = 0; i < len; i++) {
+        const uint64_t *c = &canonical[4 * i];
+        if (c[0] != v[i] || c[1] != 0 || c[2] != 0 || c[3] != 0) {
+            return 0;
+        }
+    }
+    return 1;
+}
+
+static void check_mode(uint32_t mode) {
+    const uint32_t f = VF_FIELD_BN254_FR;
+    uint64_t a[N], b[N], expected[N];
+    uint64_t x[N * 4], y[N * 4], z[N * 4], t[N * 4], out[4];
+
+    for (size_t i = 0; i < N; i++) {
+        a[i] = i + 1;
+        b[i] = 3 * i + 100;
+    }
+    from_u64(mode, x, a, N);
+    from_u64(mode, y, b, N);
+
+    CHECK(vf_add(f, mode, z, x, y, N) == VF_OK);
+    for (size_t i = 0; i < N; i++) expected[i] = a[i] + b[i];
+    CHECK(equals_u64(mode, z, expected, N));
+
+    CHECK(vf_sub(f, mode, z, y, x, N) == VF_OK);
+    for (size_t i = 0; i < N; i++) expected[i] = b[i] - a[i];
+    CHECK(equals_u64(mode, z, expected, N));
+
+    CHECK(vf_mul(f, mode, z, x, y, N) == VF_OK);
+    for (size_t i = 0; i < N; i++) expected[i] = a[i] * b[i];
+    CHECK(equals_u64(mode, z, expected, N));
+
+    CHECK(vf_add_nt(f, mode, z, x, y, N) == VF_OK);
+    for (size_t i = 0; i < N; i++) expected[i] = a[i] + b[i];
+    CHECK(equals_u64(mode, z, expected, N));
+    CHECK(vf_sub_nt(f, mode, z, y, x, N) == VF_OK);
+    for (size_t i = 0; i < N; i++) expected[i] = b[i] - a[i];
+    CHECK(equals_u64(mode, z, expected, N));
+    CHECK(vf_mul_nt(f, mode, z, x, y, N) == VF_OK);
+    for (size_t i = 0; i < N; i++) expected[i] = a[i] * b[i];
+    CHECK(equals_u64(mode, z, expected, N));
+
+    // x - y wraps around to p - (y - x).
+    uint64_t canonical[N * 4];
+    CHECK(vf_sub(f, mode, z, x, y, 1) == VF_OK);
+    CHECK(vf_to_canonical(f, mode, canonical, z, 1) == VF_OK);
+    CHECK(canonical[0] == MODULUS[0] - (b[0] - a[0]) && canonical[1] == MODULUS[1] &&
+          canonical[2] == MODULUS[2] && canonical[3] == MODULUS[3]);
+
+    memcpy(t, x, sizeof t);
+    CHECK(vf_add_inplace(f, mode, t, y, N) == VF_OK);
+    CHECK(vf_sub_inplace(f, mode, t, x, N) == VF_OK);
+    CHECK(vf_mul_inplace(f, mode, t, x, N) == VF_OK);
+    for (size_t i = 0; i < N; i++) expected[i] = a[i] * b[i];
+    CHECK(equals_u64(mode, t, expected, N));
+
+    memcpy(t, x, sizeof t);
+    CHECK(vf_sub_rev_inplace(f, mode, y, t, N) == VF_OK);
+    for (size_t i = 0; i < N; i++) expected[i] = b[i] - a[i];
+    CHECK(equals_u64(mode, t, expected, N));
+
+    uint64_t sum = 0, dot = 0;
+    for (size_t i = 0; i < N; i++) {
+        sum += a[i];
+        dot += a[i] * b[i];
+    }
+    CHECK(vf_sum(f, mode, out, x, N) == VF_OK);
+    CHECK(equals_u64(mode, out, &sum, 1));
+    CHECK(vf_inner_product(f, mode, out, x, y, N) == VF_OK);
+    CHECK(equals_u64(mode, out, &dot, 1));
+
+    uint64_t r_small[K] = {2, 5, 7}, r[K * 4];
+    from_u64(mode, r, r_small, K);
+    const uint64_t *v[K] = {x, y, x};
+    CHECK(vf_linear_combination(f, mode, z, r, v, K, N) == VF_OK);
+    for (size_t i = 0; i < N; i++) expected[i] = 2 * a[i] + 5 * b[i] + 7 * a[i];
+    CHECK(equals_u64(mode, z, expected, N));
+    CHECK(vf_sum_vectors(f, mode, z, v, K, N) == VF_OK);
+    for (size_t i = 0; i < N; i++) expected[i] = 2 * a[i] + b[i];
+    CHECK(equals_u64(mode, z, expected, N));
+}
+
+static void check_errors(void) {
+    const uint32_t f = VF_FIELD_BN254_FR;
+    uint64_t x[N * 4], z[N * 4];
+    uint64_t a[N];
+    size_t limbs = 0, index = 0;
+
+    for (size_t i = 0; i < N; i++) a[i] = i;
+    from_u64(VF_MODE_SERIAL, x, a, N);
+
+    CHECK(vf_field_limbs(f, &limbs) == VF_OK && limbs == 4);
+    CHECK(vf_field_limbs(99, &limbs) == VF_ERR_UNKNOWN_FIELD);
+    CHECK(vf_add(99, VF_MODE_SERIAL, z, x, x, N) == VF_ERR_UNKNOWN_FIELD);
+    CHECK(vf_add(f, 99, z, x, x, N) == VF_ERR_UNKNOWN_MODE);
+    CHECK(vf_add(f, VF_MODE_SERIAL, NULL, x, x, N) == VF_ERR_NULL_POINTER);
+    CHECK(vf_add(f, VF_MODE_SERIAL, NULL, NULL, NULL, 0) == VF_OK);
+    CHECK(vf_mul(f, VF_MODE_SERIAL, (uint64_t *)((char *)z + 4), x, x, 1) == VF_ERR_MISALIGNED);
+    CHECK(vf_add(f, VF_MODE_SERIAL, x + 4, x, x, 2) == VF_ERR_OVERLAP);
+    CHECK(vf_mul_nt(f, VF_MODE_SERIAL, x + 4, x, x, 2) == VF_ERR_OVERLAP);
+    CHECK(vf_sum(f, VF_MODE_SERIAL, x, x, N) == VF_OK);
+
+    memcpy(&x[4 * 20], MODULUS, sizeof MODULUS);
+    CHECK(vf_validate_canonical(f, VF_MODE_SERIAL, x, N, &index) == VF_ERR_NON_CANONICAL);
+    CHECK(index == 20);
+    CHECK(vf_validate_canonical(f, VF_MODE_PARALLEL, x, 20, NULL) == VF_OK);
+    CHECK(vf_mul(f, VF_MODE_PARALLEL, z, x, x, N) == VF_ERR_NON_CANONICAL);
+    CHECK(vf_to_canonical(f, VF_MODE_SERIAL, z, x, N) == VF_ERR_NON_CANONICAL);
+    CHECK(vf_from_canonical(f, VF_MODE_SERIAL, z, MODULUS, 1) == VF_ERR_NON_CANONICAL);
+}
+
+// The Fr modulus is a valid element of the larger Fq and survives arithmetic there.
+static void check_fq(void) {
+    const uint32_t f = VF_FIELD_BN254_FQ;
+    const uint64_t one[4] = {1, 0, 0, 0};
+    uint64_t x[4], y[4], z[4];
+    size_t limbs = 0;
+
+    CHECK(vf_field_limbs(f, &limbs) == VF_OK && limbs == 4);
+    CHECK(vf_validate_canonical(f, VF_MODE_SERIAL, MODULUS, 1, NULL) == VF_OK);
+    CHECK(vf_from_canonical(f, VF_MODE_SERIAL, x, MODULUS, 1) == VF_OK);
+    CHECK(vf_from_canonical(f, VF_MODE_SERIAL, y, one, 1) == VF_OK);
+    CHECK(vf_mul(f, VF_MODE_SERIAL, z, x, y, 1) == VF_OK);
+    CHECK(vf_to_canonical(f, VF_MODE_SERIAL, y, z, 1) == VF_OK);
+    CHECK(memcmp(y, MODULUS, sizeof MODULUS) == 0);
+}
+
+int main(void) {
+    check_mode(VF_MODE_SERIAL);
+    check_mode(VF_MODE_PARALLEL);
+    check_mode(VF_MODE_AUTO);
+    check_errors();
+    check_fq();
+
+    if (failures != 0) {
+        printf("%d checks failed\n", failures);
+        return 1;
+    }
+    printf("all checks passed\n");
+    return 0;
+}
@@ -0,0 +1,123 @@
+// Generated by `cargo run -- header` from src/capi.rs; do not edit.
+//
+// Elements are arrays of little-endian uint64_t limbs in Montgomery form and lengths
+// count elements. Pointers may be null only with a zero length, must be 8-byte
+// aligned and, except for the _inplace functions, outputs must not overlap inputs.
+// Every function returns VF_OK or a VF_ERR_* code, leaving outputs unspecified on error.
+// Only the BN254 fields are exported; BLS12-381 Fq is only available from Rust.
+
+#ifndef VECTORIZED_FIELDS_H
+#define VECTORIZED_FIELDS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef int32_t vf_status;
+
+// The BN254 scalar field, 4 limbs per element.
+#define VF_FIELD_BN254_FR 0
+
+// The BN254 base field, 4 limbs per element.
+#define VF_FIELD_BN254_FQ 1
+
+// Run on the calling thread.
+#define VF_MODE_SERIAL 0
+
+// Split across the global rayon pool.
+#define VF_MODE_PARALLEL 1
+
+// Pick serial, parallel or streaming stores from the length, as the `_auto` functions do.
+#define VF_MODE_AUTO 2
+
+// Success.
+#define VF_OK 0
+
+// A pointer was null with a non-zero length.
+#define VF_ERR_NULL_POINTER 1
+
+// A pointer was not aligned to 8 bytes.
+#define VF_ERR_MISALIGNED 2
+
+// The field is not one of the `VF_FIELD_*` constants.
+#define VF_ERR_UNKNOWN_FIELD 3
+
+// The mode is not one of the `VF_MODE_*` constants.
+#define VF_ERR_UNKNOWN_MODE 4
+
+// An output overlaps an input.
+#define VF_ERR_OVERLAP 5
+
+// The length does not fit in memory.
+#define VF_ERR_LENGTH 6
+
+// An input element is not below the modulus.
+#define VF_ERR_NON_CANONICAL 7
+
+// An internal error; please report it.
+#define VF_ERR_PANIC 8
+
+// Writes the number of 64-bit limbs per element of `field` to `limbs`.
+vf_status vf_field_limbs(uint32_t field, size_t *limbs);
+
+// Checks that the `len` elements of `x` are below the modulus. On `VF_ERR_NON_CANONICAL`,
+// writes the index of the first one that is not to `index` unless it is null.
+vf_status vf_validate_canonical(uint32_t field, uint32_t mode, const uint64_t *x, size_t len, size_t *index);
+
+// `z = x + y`, elementwise.
+vf_status vf_add(uint32_t field, uint32_t mode, uint64_t *z, const uint64_t *x, const uint64_t *y, size_t len);
+
+// `z = x - y`, elementwise.
+vf_status vf_sub(uint32_t field, uint32_t mode, uint64_t *z, const uint64_t *x, const uint64_t *y, size_t len);
+
+// `z = x * y`, elementwise.
+vf_status vf_mul(uint32_t field, uint32_t mode, uint64_t *z, const uint64_t *x, const uint64_t *y, size_t len);
+
+// `z = x + y`, elementwise, written with non-temporal stores that skip the cache. For outputs
+// far larger than the last-level cache that are not read again soon.
+vf_status vf_add_nt(uint32_t field, uint32_t mode, uint64_t *z, const uint64_t *x, const uint64_t *y, size_t len);
+
+// `z = x - y`, elementwise, with non-temporal stores; see `vf_add_nt`.
+vf_status vf_sub_nt(uint32_t field, uint32_t mode, uint64_t *z, const uint64_t *x, const uint64_t *y, size_t len);
+
+// `z = x * y`, elementwise, with non-temporal stores; see `vf_add_nt`.
+vf_status vf_mul_nt(uint32_t field, uint32_t mode, uint64_t *z, const uint64_t *x, const uint64_t *y, size_t len);
+
+// `x = x + y`, elementwise.
+vf_status vf_add_inplace(uint32_t field, uint32_t mode, uint64_t *x, const uint64_t *y, size_t len);
+
+// `x = x - y`, elementwise.
+vf_status vf_sub_inplace(uint32_t field, uint32_t mode, uint64_t *x, const uint64_t *y, size_t len);
+
+// `y = x - y`, elementwise.
+vf_status vf_sub_rev_inplace(uint32_t field, uint32_t mode, const uint64_t *x, uint64_t *y, size_t len);
+
+// `x = x * y`, elementwise.
+vf_status vf_mul_inplace(uint32_t field, uint32_t mode, uint64_t *x, const uint64_t *y, size_t len);
+
+// Writes the sum of the `len` elements of `x` to the element at `out`.
+vf_status vf_sum(uint32_t field, uint32_t mode, uint64_t *out, const uint64_t *x, size_t len);
+
+// Writes the inner product of `x` and `y` to the element at `out`.
+vf_status vf_inner_product(uint32_t field, uint32_t mode, uint64_t *out, const uint64_t *x, const uint64_t *y, size_t len);
+
+// `z = r[0] * v[0] + ... + r[k - 1] * v[k - 1]` for `k` vectors `v[j]` of `len` elements.
+vf_status vf_linear_combination(uint32_t field, uint32_t mode, uint64_t *z, const uint64_t *r, const uint64_t *const *v, size_t k, size_t len);
+
+// `z = v[0] + ... + v[k - 1]` for `k` vectors `v[j]` of `len` elements.
+vf_status vf_sum_vectors(uint32_t field, uint32_t mode, uint64_t *z, const uint64_t *const *v, size_t k, size_t len);
+
+// Converts `len` Montgomery-form elements `x` to canonical little-endian limbs in `z`.
+vf_status vf_to_canonical(uint32_t field, uint32_t mode, uint64_t *z, const uint64_t *x, size_t len);
+
+// Converts `len` canonical little-endian limbs `x` to Montgomery-form elements in `z`.
+vf_status vf_from_canonical(uint32_t field, uint32_t mode, uint64_t *z, const uint64_t *x, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
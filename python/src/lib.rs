@@ -0,0 +1,213 @@
+//! Python bindings for the BN254 Fr kernels.
+//!
+//! Vectors are NumPy `uint64` arrays of shape `(n, 4)` holding canonical little-endian limbs.
+//! Inputs are range-checked and converted to Montgomery form, the kernel runs without the GIL,
+//! and results are converted back to canonical limbs.
+
+use ark_bn254::Fr;
+use ark_ff::BigInt;
+use numpy::{ndarray::Axis, PyArray1, PyArray2, PyArrayMethods, PyReadonlyArray2};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use vectorized_fields::{
+    add_vec_bn254, add_vec_par_bn254, canonical_to_montgomery_bn254,
+    canonical_to_montgomery_par_bn254, inner_product_bn254, inner_product_par_bn254,
+    montgomery_to_canonical_bn254, montgomery_to_canonical_par_bn254, mul_vec_bn254,
+    mul_vec_par_bn254, sub_vec_bn254, sub_vec_par_bn254, sum_vec_bn254, sum_vec_par_bn254,
+    validate_canonical_bn254,
+};
+
+/// Copies an `(n, 4)` array out of Python, then checks and converts it to Montgomery form.
+fn to_montgomery(py: Python<'_>, x: &PyReadonlyArray2<'_, u64>, par: bool) -> PyResult<Vec<Fr>> {
+    let view = x.as_array();
+    if view.len_of(Axis(1)) != 4 {
+        return Err(PyValueError::new_err(format!(
+            "expected an array of shape (n, 4), got {:?}",
+            view.shape()
+        )));
+    }
+    let limbs: Vec<[u64; 4]> = view
+        .rows()
+        .into_iter()
+        .map(|row| [row[0], row[1], row[2], row[3]])
+        .collect();
+
+    // Canonical limbs are range-checked as they are, before the conversion relies on them.
+    let raw: Vec<Fr> = limbs
+        .iter()
+        .map(|&l| Fr::new_unchecked(BigInt(l)))
+        .collect();
+    if let Err(i) = validate_canonical_bn254(&raw) {
+        return Err(PyValueError::new_err(format!(
+            "element {i} is not below the BN254 Fr modulus"
+        )));
+    }
+
+    let mut out = raw;
+    py.allow_threads(|| {
+        if par {
+            canonical_to_montgomery_par_bn254(&limbs, &mut out)
+        } else {
+            canonical_to_montgomery_bn254(&limbs, &mut out)
+        }
+    });
+    Ok(out)
+}
+
+fn to_canonical<'py>(py: Python<'py>, x: &[Fr], par: bool) -> PyResult<Bound<'py, PyArray2<u64>>> {
+    let mut limbs = vec![[0u64; 4]; x.len()];
+    py.allow_threads(|| {
+        if par {
+            montgomery_to_canonical_par_bn254(x, &mut limbs)
+        } else {
+            montgomery_to_canonical_bn254(x, &mut limbs)
+        }
+    });
+    PyArray1::from_vec_bound(py, limbs.into_flattened()).reshape([x.len(), 4])
+}
+
+fn same_len(x: &[Fr], y: &[Fr]) -> PyResult<()> {
+    if x.len() != y.len() {
+        return Err(PyValueError::new_err(format!(
+            "length mismatch: {} and {}",
+            x.len(),
+            y.len()
+        )));
+    }
+    Ok(())
+}
+
+fn elementwise<'py>(
+    py: Python<'py>,
+    x: PyReadonlyArray2<'py, u64>,
+    y: PyReadonlyArray2<'py, u64>,
+    par: bool,
+    op: fn(&[Fr], &[Fr], &mut [Fr]),
+) -> PyResult<Bound<'py, PyArray2<u64>>> {
+    let (x, y) = (to_montgomery(py, &x, par)?, to_montgomery(py, &y, par)?);
+    same_len(&x, &y)?;
+    let mut z = vec![Fr::from(0u64); x.len()];
+    py.allow_threads(|| op(&x, &y, &mut z));
+    to_canonical(py, &z, par)
+}
+
+fn scalar<'py>(py: Python<'py>, x: Fr) -> Bound<'py, PyArray1<u64>> {
+    let mut limbs = [[0u64; 4]];
+    montgomery_to_canonical_bn254(&[x], &mut limbs);
+    PyArray1::from_slice_bound(py, &limbs[0])
+}
+
+/// `x + y`, elementwise.
+#[pyfunction]
+fn add<'py>(
+    py: Python<'py>,
+    x: PyReadonlyArray2<'py, u64>,
+    y: PyReadonlyArray2<'py, u64>,
+) -> PyResult<Bound<'py, PyArray2<u64>>> {
+    elementwise(py, x, y, false, add_vec_bn254)
+}
+
+#[pyfunction]
+fn add_par<'py>(
+    py: Python<'py>,
+    x: PyReadonlyArray2<'py, u64>,
+    y: PyReadonlyArray2<'py, u64>,
+) -> PyResult<Bound<'py, PyArray2<u64>>> {
+    elementwise(py, x, y, true, add_vec_par_bn254)
+}
+
+/// `x - y`, elementwise.
+#[pyfunction]
+fn sub<'py>(
+    py: Python<'py>,
+    x: PyReadonlyArray2<'py, u64>,
+    y: PyReadonlyArray2<'py, u64>,
+) -> PyResult<Bound<'py, PyArray2<u64>>> {
+    elementwise(py, x, y, false, sub_vec_bn254)
+}
+
+#[pyfunction]
+fn sub_par<'py>(
+    py: Python<'py>,
+    x: PyReadonlyArray2<'py, u64>,
+    y: PyReadonlyArray2<'py, u64>,
+) -> PyResult<Bound<'py, PyArray2<u64>>> {
+    elementwise(py, x, y, true, sub_vec_par_bn254)
+}
+
+/// `x * y`, elementwise.
+#[pyfunction]
+fn mul<'py>(
+    py: Python<'py>,
+    x: PyReadonlyArray2<'py, u64>,
+    y: PyReadonlyArray2<'py, u64>,
+) -> PyResult<Bound<'py, PyArray2<u64>>> {
+    elementwise(py, x, y, false, mul_vec_bn254)
+}
+
+#[pyfunction]
+fn mul_par<'py>(
+    py: Python<'py>,
+    x: PyReadonlyArray2<'py, u64>,
+    y: PyReadonlyArray2<'py, u64>,
+) -> PyResult<Bound<'py, PyArray2<u64>>> {
+    elementwise(py, x, y, true, mul_vec_par_bn254)
+}
+
+/// The sum of the elements of `x`, as an array of 4 limbs.
+#[pyfunction]
+fn sum<'py>(py: Python<'py>, x: PyReadonlyArray2<'py, u64>) -> PyResult<Bound<'py, PyArray1<u64>>> {
+    let x = to_montgomery(py, &x, false)?;
+    let total = py.allow_threads(|| sum_vec_bn254(&x));
+    Ok(scalar(py, total))
+}
+
+#[pyfunction]
+fn sum_par<'py>(
+    py: Python<'py>,
+    x: PyReadonlyArray2<'py, u64>,
+) -> PyResult<Bound<'py, PyArray1<u64>>> {
+    let x = to_montgomery(py, &x, true)?;
+    let total = py.allow_threads(|| sum_vec_par_bn254(&x));
+    Ok(scalar(py, total))
+}
+
+/// The inner product of `x` and `y`, as an array of 4 limbs.
+#[pyfunction]
+fn inner_product<'py>(
+    py: Python<'py>,
+    x: PyReadonlyArray2<'py, u64>,
+    y: PyReadonlyArray2<'py, u64>,
+) -> PyResult<Bound<'py, PyArray1<u64>>> {
+    let (x, y) = (to_montgomery(py, &x, false)?, to_montgomery(py, &y, false)?);
+    same_len(&x, &y)?;
+    let ip = py.allow_threads(|| inner_product_bn254(&x, &y));
+    Ok(scalar(py, ip))
+}
+
+#[pyfunction]
+fn inner_product_par<'py>(
+    py: Python<'py>,
+    x: PyReadonlyArray2<'py, u64>,
+    y: PyReadonlyArray2<'py, u64>,
+) -> PyResult<Bound<'py, PyArray1<u64>>> {
+    let (x, y) = (to_montgomery(py, &x, true)?, to_montgomery(py, &y, true)?);
+    same_len(&x, &y)?;
+    let ip = py.allow_threads(|| inner_product_par_bn254(&x, &y));
+    Ok(scalar(py, ip))
+}
+
+#[pymodule]
+fn vectorized_fields(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(add, m)?)?;
+    m.add_function(wrap_pyfunction!(add_par, m)?)?;
+    m.add_function(wrap_pyfunction!(sub, m)?)?;
+    m.add_function(wrap_pyfunction!(sub_par, m)?)?;
+    m.add_function(wrap_pyfunction!(mul, m)?)?;
+    m.add_function(wrap_pyfunction!(mul_par, m)?)?;
+    m.add_function(wrap_pyfunction!(sum, m)?)?;
+    m.add_function(wrap_pyfunction!(sum_par, m)?)?;
+    m.add_function(wrap_pyfunction!(inner_product, m)?)?;
+    m.add_function(wrap_pyfunction!(inner_product_par, m)?)?;
+    Ok(())
+}
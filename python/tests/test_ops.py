@@ -0,0 +1,75 @@
+"""Checks the bindings against Python big-int arithmetic modulo the BN254 Fr modulus."""
+
+import random
+
+import numpy as np
+import pytest
+
+import vectorized_fields as vf
+
+P = 0x30644E72E131A029B85045B68181585D2833E84879B9709143E1F593F0000001
+MASK = (1 << 64) - 1
+
+
+def to_array(values):
+    limbs = [[(v >> (64 * i)) & MASK for i in range(4)] for v in values]
+    return np.array(limbs, dtype=np.uint64).reshape(-1, 4)
+
+
+def to_ints(array):
+    return [sum(int(limb) << (64 * i) for i, limb in enumerate(row)) for row in np.atleast_2d(array)]
+
+
+@pytest.fixture(params=[0, 1, 17, 1003])
+def operands(request):
+    rng = random.Random(request.param)
+    x = [rng.randrange(P) for _ in range(request.param)]
+    y = [rng.randrange(P) for _ in range(request.param)]
+    # Edge values, where there is room for them.
+    x[:2] = [P - 1, 0][: len(x)]
+    return x, y
+
+
+@pytest.mark.parametrize(
+    "op, reference",
+    [
+        (vf.add, lambda a, b: (a + b) % P),
+        (vf.add_par, lambda a, b: (a + b) % P),
+        (vf.sub, lambda a, b: (a - b) % P),
+        (vf.sub_par, lambda a, b: (a - b) % P),
+        (vf.mul, lambda a, b: a * b % P),
+        (vf.mul_par, lambda a, b: a * b % P),
+    ],
+)
+def test_elementwise(operands, op, reference):
+    x, y = operands
+    z = op(to_array(x), to_array(y))
+    assert z.dtype == np.uint64
+    assert z.shape == (len(x), 4)
+    assert to_ints(z) == [reference(a, b) for a, b in zip(x, y)]
+
+
+@pytest.mark.parametrize("op", [vf.sum, vf.sum_par])
+def test_sum(operands, op):
+    x, _ = operands
+    assert to_ints(op(to_array(x))) == [sum(x) % P]
+
+
+@pytest.mark.parametrize("op", [vf.inner_product, vf.inner_product_par])
+def test_inner_product(operands, op):
+    x, y = operands
+    assert to_ints(op(to_array(x), to_array(y))) == [sum(a * b for a, b in zip(x, y)) % P]
+
+
+def test_rejects_bad_input():
+    with pytest.raises(ValueError, match="not below"):
+        vf.add(to_array([1, P]), to_array([1, 2]))
+    with pytest.raises(ValueError, match="shape"):
+        vf.mul(np.zeros((3, 3), dtype=np.uint64), np.zeros((3, 3), dtype=np.uint64))
+    with pytest.raises(ValueError, match="length"):
+        vf.inner_product(to_array([1, 2]), to_array([1]))
+
+
+def test_non_contiguous_input():
+    x = to_array(range(10))
+    assert to_ints(vf.sum(x[::2])) == [sum(range(0, 10, 2))]
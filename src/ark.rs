@@ -1,5 +1,9 @@
+// The `_par` baselines are only used by the benchmarks, which need `parallel`.
+#![cfg_attr(not(feature = "parallel"), allow(dead_code))]
+
 use ark_ff::PrimeField;
-use rayon::prelude::*;
+
+use crate::par::*;
 
 pub fn ark_batch_mul<F: PrimeField>(x: &[F], y: &[F], z: &mut [F]) {
     let len = x.len();
@@ -38,7 +42,7 @@ pub fn ark_inner_product_par<F: PrimeField>(x: &[F], y: &[F]) -> F {
     let len = x.len();
     assert_eq!(len, y.len());
 
-    let chunk_size = len / rayon::current_num_threads();
+    let chunk_size = len / crate::par::current_num_threads();
 
     x.par_chunks(chunk_size)
         .zip(y.par_chunks(chunk_size))
@@ -53,15 +57,14 @@ pub fn ark_inner_product_par<F: PrimeField>(x: &[F], y: &[F]) -> F {
 }
 
 pub fn ark_batch_sum<F: PrimeField>(x: &[F]) -> F {
-    let len = x.len();
-
     let mut z = F::zero();
-    for i in 0..len {
-        z += x[i];
+    for xi in x {
+        z += *xi;
     }
     z
 }
 
+#[allow(dead_code)]
 pub fn ark_batch_sum_par<F: PrimeField>(x: &[F], z: &mut [F]) {
     let len = x.len();
     assert_eq!(len, z.len());
@@ -116,3 +119,16 @@ pub fn ark_batch_sub_par<F: PrimeField>(x: &[F], y: &[F], z: &mut [F]) {
             *zi = *xi - *yi;
         });
 }
+
+/// `Σ_i coeffs[i] · z^i` by Horner's rule.
+pub fn ark_horner<F: PrimeField>(coeffs: &[F], z: F) -> F {
+    coeffs.iter().rev().fold(F::zero(), |acc, c| acc * z + c)
+}
+
+pub fn ark_linear_combination<F: PrimeField>(r: &[F], v: &[&[F]], z: &mut [F]) {
+    assert_eq!(r.len(), v.len());
+
+    for (i, zi) in z.iter_mut().enumerate() {
+        *zi = r.iter().zip(v).map(|(rj, vj)| *rj * vj[i]).sum();
+    }
+}
@@ -0,0 +1,72 @@
+/*
+ * Helpers for kernels that work on blocks of eight 256-bit elements with one element per
+ * 64-bit lane, shared by modvsum256 and modlc256_mont.
+ *
+ * Such a block is loaded one element per register, each 32-bit digit zero-extended into a qword,
+ * and transposed into eight digit planes so that lane e of plane d holds digit d of element e.
+ * Products and sums then run on all eight elements at once, and carries propagate from one
+ * plane to the next.
+ */
+
+// Transposes the 8x8 qwords in V0..V7, using X as scratch. Element e in V<e> on entry leaves
+// digit d of every element in the register listed below, lane e holding element e:
+//
+//  digit   0   1   2   3   4   5   6   7
+//  reg     V5  X   V1  V0  V6  V2  V4  V7
+
+#define TRANSPOSE8(V0, V1, V2, V3, V4, V5, V6, V7, X)			\
+	vpunpcklqdq	V1, V0, X;					\
+	vpunpckhqdq	V1, V0, V1;					\
+	vpunpcklqdq	V3, V2, V0;					\
+	vpunpckhqdq	V3, V2, V3;					\
+	vpunpcklqdq	V5, V4, V2;					\
+	vpunpckhqdq	V5, V4, V5;					\
+	vpunpcklqdq	V7, V6, V4;					\
+	vpunpckhqdq	V7, V6, V7;					\
+	vshufi64x2	$0x88, V0, X, V6;				\
+	vshufi64x2	$0xdd, V0, X, X;				\
+	vshufi64x2	$0x88, V3, V1, V0;				\
+	vshufi64x2	$0xdd, V3, V1, V3;				\
+	vshufi64x2	$0x88, V4, V2, V1;				\
+	vshufi64x2	$0xdd, V4, V2, V4;				\
+	vshufi64x2	$0x88, V7, V5, V2;				\
+	vshufi64x2	$0xdd, V7, V5, V7;				\
+	vshufi64x2	$0x88, V1, V6, V5;				\
+	vshufi64x2	$0xdd, V1, V6, V6;				\
+	vshufi64x2	$0x88, V4, X, V1;				\
+	vshufi64x2	$0xdd, V4, X, V4;				\
+	vshufi64x2	$0x88, V2, V0, X;				\
+	vshufi64x2	$0xdd, V2, V0, V2;				\
+	vshufi64x2	$0x88, V7, V3, V0;				\
+	vshufi64x2	$0xdd, V7, V3, V7
+
+// Element e of a block is loaded under mask k<e>, so that a final block of N < 8 elements reads
+// nothing past the end of the vectors. Sets k1..k7 to all lanes for e < N and none otherwise.
+
+#define SETMASK(E, K, N, T0, T1)					\
+	xorl	T0, T0;							\
+	movl	$0xff, T1;						\
+	cmpl	$E, N;							\
+	cmoval	T1, T0;							\
+	kmovw	T0, K
+
+#define TAILMASKS(N, T0, T1)						\
+	SETMASK(1, %k1, N, T0, T1);					\
+	SETMASK(2, %k2, N, T0, T1);					\
+	SETMASK(3, %k3, N, T0, T1);					\
+	SETMASK(4, %k4, N, T0, T1);					\
+	SETMASK(5, %k5, N, T0, T1);					\
+	SETMASK(6, %k6, N, T0, T1);					\
+	SETMASK(7, %k7, N, T0, T1)
+
+// Loads element e of the block at (P, OFF) into V<e>, zero-extending its digits
+
+#define LOAD8(P, OFF, V0, V1, V2, V3, V4, V5, V6, V7)			\
+	vpmovzxdq	0*32(P, OFF), V0;				\
+	vpmovzxdq	1*32(P, OFF), V1{%k1}{z};			\
+	vpmovzxdq	2*32(P, OFF), V2{%k2}{z};			\
+	vpmovzxdq	3*32(P, OFF), V3{%k3}{z};			\
+	vpmovzxdq	4*32(P, OFF), V4{%k4}{z};			\
+	vpmovzxdq	5*32(P, OFF), V5{%k5}{z};			\
+	vpmovzxdq	6*32(P, OFF), V6{%k6}{z};			\
+	vpmovzxdq	7*32(P, OFF), V7{%k7}{z}
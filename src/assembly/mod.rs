@@ -5,4 +5,22 @@ extern "C" {
     pub fn modsum256(z: *mut u64, x: *const u64, x_len: u32, m: *const u64);
     pub fn modadd256(z: *mut u64, x: *const u64, y: *const u64, xy_len: u64, m: *const u64);
     pub fn modsub256(z: *mut u64, x: *const u64, y: *const u64, xy_len: u64, m: *const u64);
+    pub fn modmul256_mont_nt(z: *mut u64, x: *const u64, y: *const u64, xy_len: u64, m: *const u64);
+    pub fn modadd256_nt(z: *mut u64, x: *const u64, y: *const u64, xy_len: u64, m: *const u64);
+    pub fn modsub256_nt(z: *mut u64, x: *const u64, y: *const u64, xy_len: u64, m: *const u64);
+    pub fn modlc256_mont(
+        z: *mut u64,
+        r: *const u64,
+        v: *const *const u64,
+        k: u32,
+        len: u64,
+        m: *const u64,
+    );
+    pub fn modvsum256(z: *mut u64, v: *const *const u64, k: u32, len: u64, m: *const u64);
+
+    pub fn modadd384(z: *mut u64, x: *const u64, y: *const u64, xy_len: u64, m: *const u64);
+    pub fn modsub384(z: *mut u64, x: *const u64, y: *const u64, xy_len: u64, m: *const u64);
+    pub fn modsum384(z: *mut u64, x: *const u64, x_len: u32, m: *const u64);
+    pub fn modmul384_mont(z: *mut u64, x: *const u64, y: *const u64, xy_len: u64, m: *const u64);
+    pub fn modip384_mont(z: *mut u64, x: *const u64, y: *const u64, xy_len: u32, m: *const u64);
 }
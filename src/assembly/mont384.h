@@ -0,0 +1,88 @@
+/*
+ * Montgomery multiplication of one pair of 384-bit elements, shared by modmul384_mont and
+ * modip384_mont.
+ *
+ * Word-by-word (CIOS) product with R = 2^384, using MULX with the two independent carry chains
+ * of ADCX and ADOX. The including file defines PX, PY, LEN and PM, and x and y are read from
+ * (PX, LEN) and (PY, LEN).
+ *
+ * For m below 2^383 every intermediate fits in seven words and each reduction leaves t < 2m, so
+ * t needs no carry word between rounds and one conditional subtraction of m at the end.
+ */
+
+// Seven accumulator words. Each round retires the lowest one, so the names rotate by one word
+// per round instead of moving values.
+
+#define R0	%rax
+#define R1	%rbx
+#define R2	%rbp
+#define R3	%r10
+#define R4	%r11
+#define R5	%r12
+#define R6	%r13
+
+// Product halves of MULX, which takes its other factor in rdx
+#define LO	%r14
+#define HI	%r15
+
+// t += x * y[I], with t in A0..A5 and A6 cleared here to take the top word
+
+#define MULROW(I, A0, A1, A2, A3, A4, A5, A6)				\
+	movq	I*8(PY, LEN), %rdx;					\
+	xorq	A6, A6;							\
+	mulxq	0*8(PX, LEN), LO, HI;	adcxq	LO, A0;	adoxq	HI, A1;	\
+	mulxq	1*8(PX, LEN), LO, HI;	adcxq	LO, A1;	adoxq	HI, A2;	\
+	mulxq	2*8(PX, LEN), LO, HI;	adcxq	LO, A2;	adoxq	HI, A3;	\
+	mulxq	3*8(PX, LEN), LO, HI;	adcxq	LO, A3;	adoxq	HI, A4;	\
+	mulxq	4*8(PX, LEN), LO, HI;	adcxq	LO, A4;	adoxq	HI, A5;	\
+	mulxq	5*8(PX, LEN), LO, HI;	adcxq	LO, A5;	adoxq	HI, A6;	\
+	adcq	$0, A6
+
+// t += (A0 * -m^-1 mod 2^64) * m, which clears A0 so that t / 2^64 is left in A1..A6
+
+#define REDUCE(A0, A1, A2, A3, A4, A5, A6)				\
+	movq	A0, %rdx;						\
+	imulq	6*8(PM), %rdx;						\
+	xorq	LO, LO;							\
+	mulxq	0*8(PM), LO, HI;	adcxq	LO, A0;	adoxq	HI, A1;	\
+	mulxq	1*8(PM), LO, HI;	adcxq	LO, A1;	adoxq	HI, A2;	\
+	mulxq	2*8(PM), LO, HI;	adcxq	LO, A2;	adoxq	HI, A3;	\
+	mulxq	3*8(PM), LO, HI;	adcxq	LO, A3;	adoxq	HI, A4;	\
+	mulxq	4*8(PM), LO, HI;	adcxq	LO, A4;	adoxq	HI, A5;	\
+	mulxq	5*8(PM), LO, HI;	adcxq	LO, A5;	adoxq	HI, A6;	\
+	adcq	$0, A6
+
+// x * y * 2^-384 mod m, possibly plus m, left in R6, R0, R1, R2, R3, R4
+
+#define MONTMUL								\
+	xorq	R0, R0;							\
+	xorq	R1, R1;							\
+	xorq	R2, R2;							\
+	xorq	R3, R3;							\
+	xorq	R4, R4;							\
+	xorq	R5, R5;							\
+	MULROW(0, R0, R1, R2, R3, R4, R5, R6);				\
+	REDUCE(R0, R1, R2, R3, R4, R5, R6);				\
+	MULROW(1, R1, R2, R3, R4, R5, R6, R0);				\
+	REDUCE(R1, R2, R3, R4, R5, R6, R0);				\
+	MULROW(2, R2, R3, R4, R5, R6, R0, R1);				\
+	REDUCE(R2, R3, R4, R5, R6, R0, R1);				\
+	MULROW(3, R3, R4, R5, R6, R0, R1, R2);				\
+	REDUCE(R3, R4, R5, R6, R0, R1, R2);				\
+	MULROW(4, R4, R5, R6, R0, R1, R2, R3);				\
+	REDUCE(R4, R5, R6, R0, R1, R2, R3);				\
+	MULROW(5, R5, R6, R0, R1, R2, R3, R4);				\
+	REDUCE(R5, R6, R0, R1, R2, R3, R4)
+
+// Subtracts m from T0..T5 unless that borrows, using six words at SAVE as scratch
+
+#define CONDSUB(SAVE, T0, T1, T2, T3, T4, T5)				\
+	movq	T0, 0*8(SAVE);	movq	T1, 1*8(SAVE);			\
+	movq	T2, 2*8(SAVE);	movq	T3, 3*8(SAVE);			\
+	movq	T4, 4*8(SAVE);	movq	T5, 5*8(SAVE);			\
+	subq	0*8(PM), T0;	sbbq	1*8(PM), T1;			\
+	sbbq	2*8(PM), T2;	sbbq	3*8(PM), T3;			\
+	sbbq	4*8(PM), T4;	sbbq	5*8(PM), T5;			\
+	cmovcq	0*8(SAVE), T0;	cmovcq	1*8(SAVE), T1;			\
+	cmovcq	2*8(SAVE), T2;	cmovcq	3*8(SAVE), T3;			\
+	cmovcq	4*8(SAVE), T4;	cmovcq	5*8(SAVE), T5
@@ -2,8 +2,8 @@ use crate::{
     ark::{ark_batch_mul, ark_batch_mul_par, ark_inner_product},
     sub_vec_bn254, sub_vec_par_bn254, utils,
 };
+use ark_bls12_381::Fq;
 use ark_bn254::Fr;
-use ark_ff::PrimeField;
 use ark_std::Zero;
 use rayon::prelude::*;
 use std::hint::black_box;
@@ -11,8 +11,11 @@ use std::time::Duration;
 
 use crate::ark::*;
 use crate::{
-    add_vec_bn254, add_vec_par_bn254, inner_product_bn254, inner_product_par_bn254, mul_vec_bn254,
-    mul_vec_par_bn254, sum_vec_bn254, sum_vec_par_bn254
+    add_vec_bn254, add_vec_inplace_bn254, add_vec_nt_par_bn254, add_vec_par_bn254,
+    aligned_vec_bn254, aligned_vec_huge_bn254, inner_product_bls12_381_fq, inner_product_bn254,
+    inner_product_par_bn254, linear_combination_bn254, mul_vec_bls12_381_fq, mul_vec_bn254,
+    mul_vec_inplace_bn254, mul_vec_nt_par_bn254, mul_vec_par_bn254, poly_eval_bn254,
+    poly_eval_many_bn254, sum_vec_bn254, sum_vec_par_bn254, sum_vectors_bn254, FieldVec,
 };
 
 pub fn bench_mul_single_threaded() -> (Duration, Duration) {
@@ -223,7 +226,7 @@ pub fn bench_sum_single_threaded() -> (Duration, Duration) {
     let x: Vec<Fr> = utils::rand_vec(NUM_OPS);
 
     let start = std::time::Instant::now();
-    let ark_sum = x.iter().sum::<Fr>();
+    let ark_sum = ark_batch_sum(&x);
     let duration_ark = start.elapsed();
     black_box(&ark_sum);
 
@@ -257,6 +260,246 @@ pub fn bench_sum_multi_threaded() -> (Duration, Duration) {
     (duration_ark, duration_simd)
 }
 
+pub fn bench_linear_combination_single_threaded() -> (Duration, Duration) {
+    const NUM_OPS: usize = 1_000_000;
+    const NUM_VECS: usize = 32;
+
+    let r: Vec<Fr> = utils::rand_vec(NUM_VECS);
+    let v: Vec<Vec<Fr>> = (0..NUM_VECS).map(|_| utils::rand_vec(NUM_OPS)).collect();
+    let v_refs: Vec<&[Fr]> = v.iter().map(Vec::as_slice).collect();
+    let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
+
+    let start = std::time::Instant::now();
+    ark_linear_combination(&r, &v_refs, &mut z);
+    let duration_ark = start.elapsed();
+    black_box(&z);
+
+    let mut simd_z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
+
+    let start = std::time::Instant::now();
+    linear_combination_bn254(&r, &v_refs, &mut simd_z);
+    let duration_simd = start.elapsed();
+    black_box(&simd_z);
+
+    assert_eq!(z, simd_z);
+
+    (duration_ark, duration_simd)
+}
+
+pub fn bench_poly_eval_single_threaded() -> (Duration, Duration) {
+    const NUM_COEFFS: usize = 1 << 22;
+
+    let coeffs: Vec<Fr> = utils::rand_vec(NUM_COEFFS);
+    let z: Fr = utils::rand_vec(1)[0];
+
+    let start = std::time::Instant::now();
+    let ark_eval = ark_horner(&coeffs, z);
+    let duration_ark = start.elapsed();
+    black_box(&ark_eval);
+
+    let start = std::time::Instant::now();
+    let simd_eval = poly_eval_bn254(&coeffs, z);
+    let duration_simd = start.elapsed();
+    black_box(&simd_eval);
+
+    assert_eq!(ark_eval, simd_eval);
+
+    (duration_ark, duration_simd)
+}
+
+pub fn bench_poly_eval_many_single_threaded() -> (Duration, Duration) {
+    const NUM_COEFFS: usize = 1 << 10;
+    const NUM_POINTS: usize = 1 << 12;
+
+    let coeffs: Vec<Fr> = utils::rand_vec(NUM_COEFFS);
+    let points: Vec<Fr> = utils::rand_vec(NUM_POINTS);
+
+    let start = std::time::Instant::now();
+    let ark_evals: Vec<Fr> = points.iter().map(|&z| ark_horner(&coeffs, z)).collect();
+    let duration_ark = start.elapsed();
+    black_box(&ark_evals);
+
+    let start = std::time::Instant::now();
+    let simd_evals = poly_eval_many_bn254(&coeffs, &points);
+    let duration_simd = start.elapsed();
+    black_box(&simd_evals);
+
+    assert_eq!(ark_evals, simd_evals);
+
+    (duration_ark, duration_simd)
+}
+
+pub fn bench_mul_bls12_381_fq_single_threaded() -> (Duration, Duration) {
+    const NUM_OPS: usize = 8 * 1_000_000;
+
+    let x: Vec<Fq> = utils::rand_vec(NUM_OPS);
+    let y: Vec<Fq> = utils::rand_vec(NUM_OPS);
+    let mut ark_z: Vec<Fq> = vec![Fq::zero(); NUM_OPS];
+    let mut simd_z: Vec<Fq> = vec![Fq::zero(); NUM_OPS];
+
+    let start = std::time::Instant::now();
+    ark_batch_mul(&x, &y, ark_z.as_mut_slice());
+    let duration_ark = start.elapsed();
+    black_box(&ark_z);
+
+    let start = std::time::Instant::now();
+    mul_vec_bls12_381_fq(&x, &y, simd_z.as_mut_slice());
+    let duration_simd = start.elapsed();
+    black_box(&simd_z);
+
+    assert_eq!(ark_z, simd_z);
+
+    (duration_ark, duration_simd)
+}
+
+pub fn bench_inner_product_bls12_381_fq_single_threaded() -> (Duration, Duration) {
+    const NUM_OPS: usize = 8 * 1_000_000;
+
+    let x: Vec<Fq> = utils::rand_vec(NUM_OPS);
+    let y: Vec<Fq> = utils::rand_vec(NUM_OPS);
+
+    let start = std::time::Instant::now();
+    let ark_z = ark_inner_product(&x, &y);
+    let duration_ark = start.elapsed();
+    black_box(&ark_z);
+
+    let start = std::time::Instant::now();
+    let simd_z = inner_product_bls12_381_fq(&x, &y);
+    let duration_simd = start.elapsed();
+    black_box(&simd_z);
+
+    assert_eq!(ark_z, simd_z);
+
+    (duration_ark, duration_simd)
+}
+
+/// Single-threaded `Σ_j r[j] · v[j]` over `num_vecs` vectors, as the scale and add passes it
+/// replaces and as one `linear_combination_bn254` pass.
+pub fn bench_linear_combination_passes(num_vecs: usize) -> (Duration, Duration) {
+    const NUM_OPS: usize = 1 << 18;
+
+    let r: Vec<Fr> = utils::rand_vec(num_vecs);
+    let v: Vec<Vec<Fr>> = (0..num_vecs).map(|_| utils::rand_vec(NUM_OPS)).collect();
+    let v_refs: Vec<&[Fr]> = v.iter().map(Vec::as_slice).collect();
+    let mut z: Vec<Fr> = v[0].clone();
+    let mut scaled: Vec<Fr> = v[0].clone();
+    let mut simd_z: Vec<Fr> = v[0].clone();
+
+    let start = std::time::Instant::now();
+    z.fill(r[0]);
+    mul_vec_inplace_bn254(&mut z, &v[0]);
+    for (rj, vj) in r.iter().zip(&v).skip(1) {
+        scaled.fill(*rj);
+        mul_vec_inplace_bn254(&mut scaled, vj);
+        add_vec_inplace_bn254(&mut z, &scaled);
+    }
+    let duration_passes = start.elapsed();
+    black_box(&z);
+
+    let start = std::time::Instant::now();
+    linear_combination_bn254(&r, &v_refs, &mut simd_z);
+    let duration_simd = start.elapsed();
+    black_box(&simd_z);
+
+    assert_eq!(z, simd_z);
+
+    (duration_passes, duration_simd)
+}
+
+/// Single-threaded `Σ_j v[j]` over `num_vecs` vectors, as the `num_vecs - 1` add passes it
+/// replaces and as one `sum_vectors_bn254` pass.
+pub fn bench_sum_vectors_single_threaded(num_vecs: usize) -> (Duration, Duration) {
+    const NUM_OPS: usize = 1 << 18;
+
+    let v: Vec<Vec<Fr>> = (0..num_vecs).map(|_| utils::rand_vec(NUM_OPS)).collect();
+    let v_refs: Vec<&[Fr]> = v.iter().map(Vec::as_slice).collect();
+    let mut z: Vec<Fr> = v[0].clone();
+    let mut simd_z: Vec<Fr> = v[0].clone();
+
+    let start = std::time::Instant::now();
+    add_vec_bn254(&v[0], &v[1], &mut z);
+    for vj in &v[2..] {
+        add_vec_inplace_bn254(&mut z, vj);
+    }
+    let duration_passes = start.elapsed();
+    black_box(&z);
+
+    let start = std::time::Instant::now();
+    sum_vectors_bn254(&v_refs, &mut simd_z);
+    let duration_simd = start.elapsed();
+    black_box(&simd_z);
+
+    assert_eq!(z, simd_z);
+
+    (duration_passes, duration_simd)
+}
+
+/// Single-threaded mul on unaligned, 64-byte aligned and huge-page backed buffers.
+///
+/// The unaligned inputs sit at different offsets within a cache line, like independently
+/// allocated `Vec<Fr>`s often do, so the kernel cannot line up its ZMM loads.
+pub fn bench_mul_alignment() -> (Duration, Duration, Duration) {
+    const NUM_OPS: usize = 8 * 4_000_000;
+
+    let x: Vec<Fr> = utils::rand_vec(NUM_OPS);
+    let y: Vec<Fr> = utils::rand_vec(NUM_OPS);
+
+    let time_mul = |alloc: fn(usize) -> FieldVec<Fr>, x_offset: usize| {
+        let mut buf_x = alloc(NUM_OPS + x_offset);
+        let mut buf_y = alloc(NUM_OPS);
+        let mut buf_z = alloc(NUM_OPS);
+        buf_x[x_offset..].copy_from_slice(&x);
+        buf_y.copy_from_slice(&y);
+
+        let start = std::time::Instant::now();
+        mul_vec_bn254(&buf_x[x_offset..], &buf_y, &mut buf_z);
+        let duration = start.elapsed();
+        black_box(&buf_z);
+        duration
+    };
+
+    let unaligned = time_mul(aligned_vec_bn254, 1);
+    let aligned = time_mul(aligned_vec_bn254, 0);
+    let huge = time_mul(aligned_vec_huge_bn254, 0);
+
+    (unaligned, aligned, huge)
+}
+
+/// Multi-threaded `op` with regular and non-temporal stores into a 2 GiB output.
+///
+/// The output is written once beforehand so that page faults are not part of either timing.
+fn bench_streaming(
+    op: fn(&[Fr], &[Fr], &mut [Fr]),
+    op_nt: fn(&[Fr], &[Fr], &mut [Fr]),
+) -> (Duration, Duration) {
+    const NUM_OPS: usize = 1 << 26;
+
+    let x: Vec<Fr> = utils::rand_vec(NUM_OPS);
+    let y: Vec<Fr> = utils::rand_vec(NUM_OPS);
+    let mut z = aligned_vec_bn254(NUM_OPS);
+    op(&x, &y, &mut z);
+
+    let start = std::time::Instant::now();
+    op(&x, &y, &mut z);
+    let duration = start.elapsed();
+    black_box(&z);
+
+    let start = std::time::Instant::now();
+    op_nt(&x, &y, &mut z);
+    let duration_nt = start.elapsed();
+    black_box(&z);
+
+    (duration, duration_nt)
+}
+
+pub fn bench_add_nt_multi_threaded() -> (Duration, Duration) {
+    bench_streaming(add_vec_par_bn254, add_vec_nt_par_bn254)
+}
+
+pub fn bench_mul_nt_multi_threaded() -> (Duration, Duration) {
+    bench_streaming(mul_vec_par_bn254, mul_vec_nt_par_bn254)
+}
+
 pub fn benchmark() {
     let (ark_mul_single, simd_mul_single) = bench_mul_single_threaded();
     let (ark_mul_multi, simd_mul_multi) = bench_mul_multi_threaded();
@@ -268,17 +511,175 @@ pub fn benchmark() {
     let (ark_sub_multi, simd_sub_multi) = bench_sub_multi_threaded();
     let (ark_sum_single, simd_sum_single) = bench_sum_single_threaded();
     let (ark_sum_multi, simd_sum_multi) = bench_sum_multi_threaded();
+    let (ark_lc_single, simd_lc_single) = bench_linear_combination_single_threaded();
+    let (ark_poly_single, simd_poly_single) = bench_poly_eval_single_threaded();
+    let (ark_poly_many, simd_poly_many) = bench_poly_eval_many_single_threaded();
+    let (ark_bls_mul, simd_bls_mul) = bench_mul_bls12_381_fq_single_threaded();
+    let (ark_bls_inner, simd_bls_inner) = bench_inner_product_bls12_381_fq_single_threaded();
+    let sum_vectors: Vec<(usize, Duration, Duration)> = [4, 16, 64]
+        .into_iter()
+        .map(|k| {
+            let (passes, simd) = bench_sum_vectors_single_threaded(k);
+            (k, passes, simd)
+        })
+        .collect();
+    let linear_combination: Vec<(usize, Duration, Duration)> = [4, 16, 64]
+        .into_iter()
+        .map(|k| {
+            let (passes, simd) = bench_linear_combination_passes(k);
+            (k, passes, simd)
+        })
+        .collect();
+    let (mul_unaligned, mul_aligned, mul_huge) = bench_mul_alignment();
+    let (add_cached, add_nt) = bench_add_nt_multi_threaded();
+    let (mul_cached, mul_nt) = bench_mul_nt_multi_threaded();
 
     println!("| Benchmark | Arkworks | AVX-512 | Speedup |");
     println!("|-----------|----------|---------|---------|");
-    println!("| Mul Single-Threaded | {:?} | {:?} | {:.2}x |", ark_mul_single, simd_mul_single, ark_mul_single.as_secs_f64() / simd_mul_single.as_secs_f64());
-    println!("| Mul Multi-Threaded | {:?} | {:?} | {:.2}x |", ark_mul_multi, simd_mul_multi, ark_mul_multi.as_secs_f64() / simd_mul_multi.as_secs_f64());
-    println!("| Inner Product Single-Threaded | {:?} | {:?} | {:.2}x |", ark_inner_single, simd_inner_single, ark_inner_single.as_secs_f64() / simd_inner_single.as_secs_f64());
-    println!("| Inner Product Multi-Threaded | {:?} | {:?} | {:.2}x |", ark_inner_multi, simd_inner_multi, ark_inner_multi.as_secs_f64() / simd_inner_multi.as_secs_f64());
-    println!("| Add Single-Threaded | {:?} | {:?} | {:.2}x |", ark_add_single, simd_add_single, ark_add_single.as_secs_f64() / simd_add_single.as_secs_f64());
-    println!("| Add Multi-Threaded | {:?} | {:?} | {:.2}x |", ark_add_multi, simd_add_multi, ark_add_multi.as_secs_f64() / simd_add_multi.as_secs_f64());
-    println!("| Sub Single-Threaded | {:?} | {:?} | {:.2}x |", ark_sub_single, simd_sub_single, ark_sub_single.as_secs_f64() / simd_sub_single.as_secs_f64());
-    println!("| Sub Multi-Threaded | {:?} | {:?} | {:.2}x |", ark_sub_multi, simd_sub_multi, ark_sub_multi.as_secs_f64() / simd_sub_multi.as_secs_f64());
-    println!("| Sum Single-Threaded | {:?} | {:?} | {:.2}x |", ark_sum_single, simd_sum_single, ark_sum_single.as_secs_f64() / simd_sum_single.as_secs_f64());
-    println!("| Sum Multi-Threaded | {:?} | {:?} | {:.2}x |", ark_sum_multi, simd_sum_multi, ark_sum_multi.as_secs_f64() / simd_sum_multi.as_secs_f64());
+    println!(
+        "| Mul Single-Threaded | {:?} | {:?} | {:.2}x |",
+        ark_mul_single,
+        simd_mul_single,
+        ark_mul_single.as_secs_f64() / simd_mul_single.as_secs_f64()
+    );
+    println!(
+        "| Mul Multi-Threaded | {:?} | {:?} | {:.2}x |",
+        ark_mul_multi,
+        simd_mul_multi,
+        ark_mul_multi.as_secs_f64() / simd_mul_multi.as_secs_f64()
+    );
+    println!(
+        "| Inner Product Single-Threaded | {:?} | {:?} | {:.2}x |",
+        ark_inner_single,
+        simd_inner_single,
+        ark_inner_single.as_secs_f64() / simd_inner_single.as_secs_f64()
+    );
+    println!(
+        "| Inner Product Multi-Threaded | {:?} | {:?} | {:.2}x |",
+        ark_inner_multi,
+        simd_inner_multi,
+        ark_inner_multi.as_secs_f64() / simd_inner_multi.as_secs_f64()
+    );
+    println!(
+        "| Add Single-Threaded | {:?} | {:?} | {:.2}x |",
+        ark_add_single,
+        simd_add_single,
+        ark_add_single.as_secs_f64() / simd_add_single.as_secs_f64()
+    );
+    println!(
+        "| Add Multi-Threaded | {:?} | {:?} | {:.2}x |",
+        ark_add_multi,
+        simd_add_multi,
+        ark_add_multi.as_secs_f64() / simd_add_multi.as_secs_f64()
+    );
+    println!(
+        "| Sub Single-Threaded | {:?} | {:?} | {:.2}x |",
+        ark_sub_single,
+        simd_sub_single,
+        ark_sub_single.as_secs_f64() / simd_sub_single.as_secs_f64()
+    );
+    println!(
+        "| Sub Multi-Threaded | {:?} | {:?} | {:.2}x |",
+        ark_sub_multi,
+        simd_sub_multi,
+        ark_sub_multi.as_secs_f64() / simd_sub_multi.as_secs_f64()
+    );
+    println!(
+        "| Sum Single-Threaded | {:?} | {:?} | {:.2}x |",
+        ark_sum_single,
+        simd_sum_single,
+        ark_sum_single.as_secs_f64() / simd_sum_single.as_secs_f64()
+    );
+    println!(
+        "| Sum Multi-Threaded | {:?} | {:?} | {:.2}x |",
+        ark_sum_multi,
+        simd_sum_multi,
+        ark_sum_multi.as_secs_f64() / simd_sum_multi.as_secs_f64()
+    );
+    println!(
+        "| Linear Combination Single-Threaded | {:?} | {:?} | {:.2}x |",
+        ark_lc_single,
+        simd_lc_single,
+        ark_lc_single.as_secs_f64() / simd_lc_single.as_secs_f64()
+    );
+    println!(
+        "| Poly Eval Single-Threaded | {:?} | {:?} | {:.2}x |",
+        ark_poly_single,
+        simd_poly_single,
+        ark_poly_single.as_secs_f64() / simd_poly_single.as_secs_f64()
+    );
+    println!(
+        "| Poly Eval Many Single-Threaded | {:?} | {:?} | {:.2}x |",
+        ark_poly_many,
+        simd_poly_many,
+        ark_poly_many.as_secs_f64() / simd_poly_many.as_secs_f64()
+    );
+
+    // The 384-bit kernels have no AVX-512 path; these rows time their scalar MULX/ADX code.
+    println!();
+    println!("| Benchmark | Arkworks | MULX/ADX | Speedup |");
+    println!("|-----------|----------|----------|---------|");
+    println!(
+        "| BLS12-381 Fq Mul Single-Threaded | {:?} | {:?} | {:.2}x |",
+        ark_bls_mul,
+        simd_bls_mul,
+        ark_bls_mul.as_secs_f64() / simd_bls_mul.as_secs_f64()
+    );
+    println!(
+        "| BLS12-381 Fq Inner Product Single-Threaded | {:?} | {:?} | {:.2}x |",
+        ark_bls_inner,
+        simd_bls_inner,
+        ark_bls_inner.as_secs_f64() / simd_bls_inner.as_secs_f64()
+    );
+    println!();
+    println!("| Benchmark | Separate Passes | One Pass | Speedup |");
+    println!("|-----------|-----------------|----------|---------|");
+    for (k, passes, simd) in &sum_vectors {
+        println!(
+            "| Sum of {} Vectors Single-Threaded | {:?} | {:?} | {:.2}x |",
+            k,
+            passes,
+            simd,
+            passes.as_secs_f64() / simd.as_secs_f64()
+        );
+    }
+    for (k, passes, simd) in &linear_combination {
+        println!(
+            "| Linear Combination of {} Vectors Single-Threaded | {:?} | {:?} | {:.2}x |",
+            k,
+            passes,
+            simd,
+            passes.as_secs_f64() / simd.as_secs_f64()
+        );
+    }
+    println!();
+    println!("| Benchmark | Unaligned | Aligned | Speedup |");
+    println!("|-----------|-----------|---------|---------|");
+    println!(
+        "| Mul 64-byte Aligned | {:?} | {:?} | {:.2}x |",
+        mul_unaligned,
+        mul_aligned,
+        mul_unaligned.as_secs_f64() / mul_aligned.as_secs_f64()
+    );
+    println!(
+        "| Mul Huge Pages | {:?} | {:?} | {:.2}x |",
+        mul_unaligned,
+        mul_huge,
+        mul_unaligned.as_secs_f64() / mul_huge.as_secs_f64()
+    );
+    println!();
+    println!("| Benchmark | Regular Stores | Non-Temporal Stores | Speedup |");
+    println!("|-----------|----------------|---------------------|---------|");
+    println!(
+        "| Add Multi-Threaded | {:?} | {:?} | {:.2}x |",
+        add_cached,
+        add_nt,
+        add_cached.as_secs_f64() / add_nt.as_secs_f64()
+    );
+    println!(
+        "| Mul Multi-Threaded | {:?} | {:?} | {:.2}x |",
+        mul_cached,
+        mul_nt,
+        mul_cached.as_secs_f64() / mul_nt.as_secs_f64()
+    );
 }
@@ -0,0 +1,96 @@
+//! The vector kernels over the BLS12-381 base field `Fq`.
+//!
+//! At 381 bits `Fq` does not fit the 256-bit kernels, so these are the [`limbs384`] operations
+//! run against [`Modulus384::BLS12_381_FQ`].
+
+use ark_bls12_381::Fq;
+
+use crate::{
+    limbs384::{self, Modulus384},
+    utils::{field_functions, limbs, limbs_mut},
+};
+
+field_functions! {
+    Fq, limbs384, &Modulus384::BLS12_381_FQ, parity_ark_bls12_381_fq;
+    [
+        add_vec_bls12_381_fq, add_vec_par_bls12_381_fq,
+        add_vec_inplace_bls12_381_fq, add_vec_inplace_par_bls12_381_fq,
+        sub_vec_bls12_381_fq, sub_vec_par_bls12_381_fq,
+        sub_vec_inplace_bls12_381_fq, sub_vec_inplace_par_bls12_381_fq,
+        mul_vec_bls12_381_fq, mul_vec_par_bls12_381_fq,
+        mul_vec_inplace_bls12_381_fq, mul_vec_inplace_par_bls12_381_fq,
+        sum_vec_bls12_381_fq, sum_vec_par_bls12_381_fq,
+        inner_product_bls12_381_fq, inner_product_par_bls12_381_fq,
+    ]
+}
+
+/// Converts Montgomery-form elements to their canonical little-endian limbs.
+pub fn montgomery_to_canonical_bls12_381_fq(x: &[Fq], z: &mut [[u64; 6]]) {
+    limbs384::from_montgomery(&Modulus384::BLS12_381_FQ, limbs(x), z)
+}
+
+/// Converts canonical little-endian limbs, each below the modulus, to Montgomery-form elements.
+pub fn canonical_to_montgomery_bls12_381_fq(x: &[[u64; 6]], z: &mut [Fq]) {
+    limbs384::to_montgomery(&Modulus384::BLS12_381_FQ, x, limbs_mut(z))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ark::*, utils::rand_vec};
+    use ark_ff::{Field, PrimeField};
+    use ark_std::{One, Zero};
+
+    #[test]
+    fn parity_ark_bls12_381_fq_extremes() {
+        // The largest residues exercise every carry and the final subtractions.
+        let max = -Fq::one();
+        let values = [
+            Fq::zero(),
+            Fq::one(),
+            max,
+            max + max,
+            Fq::from(Fq::MODULUS_BIT_SIZE as u64).inverse().unwrap(),
+        ];
+        let x: Vec<Fq> = values
+            .iter()
+            .flat_map(|a| values.iter().map(move |_| *a))
+            .collect();
+        let y: Vec<Fq> = values.iter().flat_map(|_| values.iter().copied()).collect();
+        let mut ark_z = vec![Fq::zero(); x.len()];
+        let mut z = vec![Fq::zero(); x.len()];
+
+        ark_batch_add(&x, &y, &mut ark_z);
+        add_vec_bls12_381_fq(&x, &y, &mut z);
+        assert_eq!(z, ark_z);
+
+        ark_batch_sub(&x, &y, &mut ark_z);
+        sub_vec_bls12_381_fq(&x, &y, &mut z);
+        assert_eq!(z, ark_z);
+
+        ark_batch_mul(&x, &y, &mut ark_z);
+        mul_vec_bls12_381_fq(&x, &y, &mut z);
+        assert_eq!(z, ark_z);
+
+        assert_eq!(sum_vec_bls12_381_fq(&x), ark_batch_sum(&x));
+        assert_eq!(
+            inner_product_bls12_381_fq(&x, &y),
+            ark_inner_product(&x, &y)
+        );
+    }
+
+    #[test]
+    fn parity_ark_bls12_381_fq_canonical_conversion() {
+        const NUM_OPS: usize = 2_500;
+        let x: Vec<Fq> = rand_vec(NUM_OPS);
+        let ark_canonical: Vec<[u64; 6]> = x.iter().map(|xi| xi.into_bigint().0).collect();
+
+        let mut canonical = vec![[0u64; 6]; NUM_OPS];
+        montgomery_to_canonical_bls12_381_fq(&x, &mut canonical);
+        assert_eq!(canonical, ark_canonical);
+
+        let mut montgomery = vec![Fq::zero(); NUM_OPS];
+        canonical_to_montgomery_bls12_381_fq(&canonical, &mut montgomery);
+        assert_eq!(montgomery, x);
+    }
+}
@@ -0,0 +1,581 @@
+//! The arkworks API for BN254 `Fr`: each function casts its `Fr` slices to raw limbs and runs
+//! the [`limbs`] operation with [`Modulus::BN254_FR`].
+
+use ark_bn254::Fr;
+
+use crate::{
+    limbs::{self, Modulus},
+    utils::{from_limbs, limbs, limbs_mut},
+    FieldVec,
+};
+
+const FR: &Modulus = &Modulus::BN254_FR;
+
+pub fn add_vec_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
+    limbs::add(FR, limbs(x), limbs(y), limbs_mut(z))
+}
+
+pub fn add_vec_par_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
+    limbs::add_par(FR, limbs(x), limbs(y), limbs_mut(z))
+}
+
+pub fn add_vec_inplace_bn254(x: &mut [Fr], y: &[Fr]) {
+    limbs::add_inplace(FR, limbs_mut(x), limbs(y))
+}
+
+pub fn add_vec_inplace_par_bn254(x: &mut [Fr], y: &[Fr]) {
+    limbs::add_inplace_par(FR, limbs_mut(x), limbs(y))
+}
+
+/// `z = x + y` written with non-temporal stores, which skip the read-for-ownership of `z`.
+/// Faster than [`add_vec_bn254`] when `z` is far larger than the last-level cache and not
+/// read again soon, slower otherwise.
+pub fn add_vec_nt_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
+    limbs::add_nt(FR, limbs(x), limbs(y), limbs_mut(z))
+}
+
+pub fn add_vec_nt_par_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
+    limbs::add_nt_par(FR, limbs(x), limbs(y), limbs_mut(z))
+}
+
+pub fn sub_vec_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
+    limbs::sub(FR, limbs(x), limbs(y), limbs_mut(z))
+}
+
+pub fn sub_vec_par_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
+    limbs::sub_par(FR, limbs(x), limbs(y), limbs_mut(z))
+}
+
+pub fn sub_vec_inplace_bn254(x: &mut [Fr], y: &[Fr]) {
+    limbs::sub_inplace(FR, limbs_mut(x), limbs(y))
+}
+
+pub fn sub_vec_inplace_par_bn254(x: &mut [Fr], y: &[Fr]) {
+    limbs::sub_inplace_par(FR, limbs_mut(x), limbs(y))
+}
+
+/// Reverse subtraction `y = x - y`, writing into `y`.
+pub fn sub_vec_rev_inplace_bn254(x: &[Fr], y: &mut [Fr]) {
+    limbs::sub_rev_inplace(FR, limbs(x), limbs_mut(y))
+}
+
+pub fn sub_vec_rev_inplace_par_bn254(x: &[Fr], y: &mut [Fr]) {
+    limbs::sub_rev_inplace_par(FR, limbs(x), limbs_mut(y))
+}
+
+/// `z = x - y` written with non-temporal stores, which skip the read-for-ownership of `z`.
+/// Faster than [`sub_vec_bn254`] when `z` is far larger than the last-level cache and not
+/// read again soon, slower otherwise.
+pub fn sub_vec_nt_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
+    limbs::sub_nt(FR, limbs(x), limbs(y), limbs_mut(z))
+}
+
+pub fn sub_vec_nt_par_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
+    limbs::sub_nt_par(FR, limbs(x), limbs(y), limbs_mut(z))
+}
+
+pub fn sum_vec_bn254(x: &[Fr]) -> Fr {
+    from_limbs(limbs::sum(FR, limbs(x)))
+}
+
+pub fn sum_vec_par_bn254(x: &[Fr]) -> Fr {
+    from_limbs(limbs::sum_par(FR, limbs(x)))
+}
+
+/// Vector of `len` zeros in 64-byte aligned storage, so the mul kernels take their aligned path.
+/// The add and sub kernels work one 64-bit limb at a time and run the same at any alignment.
+pub fn aligned_vec_bn254(len: usize) -> FieldVec<Fr> {
+    FieldVec::zeros(len)
+}
+
+/// [`aligned_vec_bn254`] backed by huge pages where the OS provides them, for tables large
+/// enough to thrash the TLB.
+pub fn aligned_vec_huge_bn254(len: usize) -> FieldVec<Fr> {
+    FieldVec::zeros_huge(len)
+}
+
+pub fn mul_vec_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
+    limbs::mul(FR, limbs(x), limbs(y), limbs_mut(z))
+}
+
+pub fn mul_vec_par_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
+    limbs::mul_par(FR, limbs(x), limbs(y), limbs_mut(z))
+}
+
+pub fn mul_vec_inplace_bn254(x: &mut [Fr], y: &[Fr]) {
+    limbs::mul_inplace(FR, limbs_mut(x), limbs(y))
+}
+
+pub fn mul_vec_inplace_par_bn254(x: &mut [Fr], y: &[Fr]) {
+    limbs::mul_inplace_par(FR, limbs_mut(x), limbs(y))
+}
+
+/// `z = x * y` written with non-temporal stores; see [`add_vec_nt_bn254`]. `z` should start at a
+/// multiple of 32 bytes, as `FieldVec` and most allocators guarantee, to take the streaming path.
+pub fn mul_vec_nt_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
+    limbs::mul_nt(FR, limbs(x), limbs(y), limbs_mut(z))
+}
+
+pub fn mul_vec_nt_par_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
+    limbs::mul_nt_par(FR, limbs(x), limbs(y), limbs_mut(z))
+}
+
+/// Converts Montgomery-form elements to their canonical little-endian limbs.
+pub fn montgomery_to_canonical_bn254(x: &[Fr], z: &mut [[u64; 4]]) {
+    limbs::from_montgomery(FR, limbs(x), z)
+}
+
+/// Converts canonical little-endian limbs, each below the modulus, to Montgomery-form elements.
+pub fn canonical_to_montgomery_bn254(x: &[[u64; 4]], z: &mut [Fr]) {
+    limbs::to_montgomery(FR, x, limbs_mut(z))
+}
+
+pub fn montgomery_to_canonical_par_bn254(x: &[Fr], z: &mut [[u64; 4]]) {
+    limbs::from_montgomery_par(FR, limbs(x), z)
+}
+
+pub fn canonical_to_montgomery_par_bn254(x: &[[u64; 4]], z: &mut [Fr]) {
+    limbs::to_montgomery_par(FR, x, limbs_mut(z))
+}
+
+pub fn inner_product_bn254(x: &[Fr], y: &[Fr]) -> Fr {
+    from_limbs(limbs::inner_product(FR, limbs(x), limbs(y)))
+}
+
+pub fn inner_product_par_bn254(x: &[Fr], y: &[Fr]) -> Fr {
+    from_limbs(limbs::inner_product_par(FR, limbs(x), limbs(y)))
+}
+
+pub fn add_vec_auto_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
+    limbs::add_auto(FR, limbs(x), limbs(y), limbs_mut(z))
+}
+
+pub fn add_vec_inplace_auto_bn254(x: &mut [Fr], y: &[Fr]) {
+    limbs::add_inplace_auto(FR, limbs_mut(x), limbs(y))
+}
+
+pub fn sub_vec_auto_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
+    limbs::sub_auto(FR, limbs(x), limbs(y), limbs_mut(z))
+}
+
+pub fn sub_vec_inplace_auto_bn254(x: &mut [Fr], y: &[Fr]) {
+    limbs::sub_inplace_auto(FR, limbs_mut(x), limbs(y))
+}
+
+pub fn mul_vec_auto_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
+    limbs::mul_auto(FR, limbs(x), limbs(y), limbs_mut(z))
+}
+
+pub fn mul_vec_inplace_auto_bn254(x: &mut [Fr], y: &[Fr]) {
+    limbs::mul_inplace_auto(FR, limbs_mut(x), limbs(y))
+}
+
+pub fn sum_vec_auto_bn254(x: &[Fr]) -> Fr {
+    from_limbs(limbs::sum_auto(FR, limbs(x)))
+}
+
+pub fn inner_product_auto_bn254(x: &[Fr], y: &[Fr]) -> Fr {
+    from_limbs(limbs::inner_product_auto(FR, limbs(x), limbs(y)))
+}
+
+/// `z = Σ_j r[j] · v[j]`, reading each vector once and reducing each element once.
+pub fn linear_combination_bn254(r: &[Fr], v: &[&[Fr]], z: &mut [Fr]) {
+    let v: Vec<&[[u64; 4]]> = v.iter().map(|vj| limbs(vj)).collect();
+    limbs::linear_combination(FR, limbs(r), &v, limbs_mut(z))
+}
+
+pub fn linear_combination_par_bn254(r: &[Fr], v: &[&[Fr]], z: &mut [Fr]) {
+    let v: Vec<&[[u64; 4]]> = v.iter().map(|vj| limbs(vj)).collect();
+    limbs::linear_combination_par(FR, limbs(r), &v, limbs_mut(z))
+}
+
+/// `z = Σ_j v[j]`, reading each vector once and reducing each element once.
+pub fn sum_vectors_bn254(v: &[&[Fr]], z: &mut [Fr]) {
+    let v: Vec<&[[u64; 4]]> = v.iter().map(|vj| limbs(vj)).collect();
+    limbs::sum_vectors(FR, &v, limbs_mut(z))
+}
+
+pub fn sum_vectors_par_bn254(v: &[&[Fr]], z: &mut [Fr]) {
+    let v: Vec<&[[u64; 4]]> = v.iter().map(|vj| limbs(vj)).collect();
+    limbs::sum_vectors_par(FR, &v, limbs_mut(z))
+}
+
+/// Checks that every element of `x` is a canonical residue, i.e. its Montgomery limbs are below
+/// the modulus, as the kernels require. Returns the index of the first element that is not.
+///
+/// Arkworks never produces such elements, but `Fr::new_unchecked`, raw pointer casts and FFI
+/// callers can.
+pub fn validate_canonical_bn254(x: &[Fr]) -> Result<(), usize> {
+    limbs::validate(FR, limbs(x))
+}
+
+pub fn validate_canonical_par_bn254(x: &[Fr]) -> Result<(), usize> {
+    limbs::validate_par(FR, limbs(x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ark::*;
+    use crate::schedule;
+    use crate::utils::rand_vec;
+    use ark_ff::PrimeField;
+    use ark_std::{One, Zero};
+
+    #[test]
+    fn parallel_parity_vec_mul() {
+        const NUM_OPS: usize = 10_000;
+        let x: Vec<Fr> = rand_vec(NUM_OPS);
+        let y: Vec<Fr> = rand_vec(NUM_OPS);
+        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
+
+        let x_par = x.clone();
+        let y_par = y.clone();
+        let mut z_par = z.clone();
+
+        mul_vec_bn254(&x, &y, &mut z);
+        mul_vec_par_bn254(&x_par, &y_par, &mut z_par);
+        assert_eq!(z, z_par);
+    }
+
+    #[test]
+    fn parallel_parity_inner_product() {
+        const NUM_OPS: usize = 10_000;
+        let x: Vec<Fr> = rand_vec(NUM_OPS);
+        let y: Vec<Fr> = rand_vec(NUM_OPS);
+
+        let z = inner_product_bn254(&x, &y);
+        let par_z = inner_product_par_bn254(&x, &y);
+        assert_eq!(z, par_z);
+    }
+
+    #[test]
+    fn parallel_parity_vec_add() {
+        const NUM_OPS: usize = 10_000;
+        let x: Vec<Fr> = rand_vec(NUM_OPS);
+        let y: Vec<Fr> = rand_vec(NUM_OPS);
+        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
+
+        let x_par = x.clone();
+        let y_par = y.clone();
+        let mut z_par = z.clone();
+
+        add_vec_bn254(&x, &y, &mut z);
+        add_vec_par_bn254(&x_par, &y_par, &mut z_par);
+        assert_eq!(z, z_par);
+    }
+
+    #[test]
+    fn parallel_parity_vec_sub() {
+        const NUM_OPS: usize = 10_000;
+        let x: Vec<Fr> = rand_vec(NUM_OPS);
+        let y: Vec<Fr> = rand_vec(NUM_OPS);
+        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
+
+        let x_par = x.clone();
+        let y_par = y.clone();
+        let mut z_par = z.clone();
+
+        sub_vec_bn254(&x, &y, &mut z);
+        sub_vec_par_bn254(&x_par, &y_par, &mut z_par);
+        assert_eq!(z, z_par);
+    }
+
+    #[test]
+    fn parallel_parity_vec_sum() {
+        const NUM_OPS: usize = 10_000;
+        let x: Vec<Fr> = rand_vec(NUM_OPS);
+
+        let x_par = x.clone();
+
+        let z = sum_vec_bn254(&x);
+        let z_par = sum_vec_par_bn254(&x_par);
+        assert_eq!(z, z_par);
+    }
+
+    #[test]
+    fn parity_ark_mul() {
+        const NUM_OPS: usize = 5;
+        let x: Vec<Fr> = rand_vec(NUM_OPS);
+        let y: Vec<Fr> = rand_vec(NUM_OPS);
+        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
+        let mut ark_z = z.clone();
+
+        ark_batch_mul(&x, &y, &mut ark_z);
+        mul_vec_bn254(&x, &y, &mut z);
+        assert_eq!(z, ark_z);
+    }
+
+    #[test]
+    fn parity_ark_add() {
+        const NUM_OPS: usize = 5;
+        let x: Vec<Fr> = rand_vec(NUM_OPS);
+        let y: Vec<Fr> = rand_vec(NUM_OPS);
+        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
+        let mut ark_z = z.clone();
+
+        ark_batch_add(&x, &y, &mut ark_z);
+        add_vec_bn254(&x, &y, &mut z);
+        assert_eq!(z, ark_z);
+    }
+
+    #[test]
+    fn parity_ark_sub() {
+        const NUM_OPS: usize = 5;
+        let x: Vec<Fr> = rand_vec(NUM_OPS);
+        let y: Vec<Fr> = rand_vec(NUM_OPS);
+        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
+        let mut ark_z = z.clone();
+
+        ark_batch_sub(&x, &y, &mut ark_z);
+        sub_vec_bn254(&x, &y, &mut z);
+        assert_eq!(z, ark_z);
+    }
+
+    #[test]
+    fn parity_ark_inner_product() {
+        const NUM_OPS: usize = 500;
+        let x: Vec<Fr> = rand_vec(NUM_OPS);
+        let y: Vec<Fr> = rand_vec(NUM_OPS);
+
+        let ark_z = ark_inner_product(&x, &y);
+        let z = inner_product_bn254(&x, &y);
+        assert_eq!(z, ark_z);
+    }
+
+    #[test]
+    fn parity_ark_sum() {
+        const NUM_OPS: usize = 5;
+        let x: Vec<Fr> = rand_vec(NUM_OPS);
+        let ark_x = x.clone();
+
+        let ark_z = ark_batch_sum(&ark_x);
+        let z = sum_vec_bn254(&x);
+        assert_eq!(z, ark_z);
+    }
+
+    #[test]
+    fn sum_every_length_mod_four() {
+        // `modsum256` adds the `len % 4` leading elements before the main loop, which must then
+        // start after them.
+        for num_ops in 0..=12 {
+            let x: Vec<Fr> = rand_vec(num_ops);
+            assert_eq!(sum_vec_bn254(&x), ark_batch_sum(&x));
+        }
+    }
+
+    #[test]
+    fn parity_ark_linear_combination() {
+        // Lengths around the eight-element blocks, and the largest residue to drive the carries.
+        for (num_ops, num_vecs) in [(0, 3), (5, 0), (7, 1), (8, 16), (1_003, 16), (17, 100)] {
+            let mut r: Vec<Fr> = rand_vec(num_vecs);
+            let mut v: Vec<Vec<Fr>> = (0..num_vecs).map(|_| rand_vec(num_ops)).collect();
+            if let (Some(rj), Some(vj)) = (r.last_mut(), v.last_mut()) {
+                *rj = -Fr::one();
+                vj.iter_mut().step_by(3).for_each(|x| *x = -Fr::one());
+            }
+            let v_refs: Vec<&[Fr]> = v.iter().map(Vec::as_slice).collect();
+            let mut z: Vec<Fr> = vec![Fr::one(); num_ops];
+            let mut ark_z = vec![Fr::zero(); num_ops];
+
+            ark_linear_combination(&r, &v_refs, &mut ark_z);
+            linear_combination_bn254(&r, &v_refs, &mut z);
+            assert_eq!(z, ark_z);
+        }
+
+        let max = vec![-Fr::one(); 9];
+        let r = vec![-Fr::one(); 1_000];
+        let v_refs = vec![max.as_slice(); 1_000];
+        let mut z = vec![Fr::zero(); 9];
+        linear_combination_bn254(&r, &v_refs, &mut z);
+        assert_eq!(z, vec![Fr::from(1_000u64); 9]);
+    }
+
+    #[test]
+    fn parallel_parity_linear_combination() {
+        const NUM_OPS: usize = 10_000;
+        const NUM_VECS: usize = 10;
+        let r: Vec<Fr> = rand_vec(NUM_VECS);
+        let v: Vec<Vec<Fr>> = (0..NUM_VECS).map(|_| rand_vec(NUM_OPS)).collect();
+        let v_refs: Vec<&[Fr]> = v.iter().map(Vec::as_slice).collect();
+        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
+        let mut z_par = z.clone();
+
+        linear_combination_bn254(&r, &v_refs, &mut z);
+        linear_combination_par_bn254(&r, &v_refs, &mut z_par);
+        assert_eq!(z, z_par);
+    }
+
+    #[test]
+    fn parity_ark_sum_vectors() {
+        // Lengths around the eight-element blocks, and the largest residue to drive the carries.
+        for (num_ops, num_vecs) in [(0, 3), (5, 0), (7, 1), (8, 13), (1_003, 13), (17, 100)] {
+            let mut v: Vec<Vec<Fr>> = (0..num_vecs).map(|_| rand_vec(num_ops)).collect();
+            if let Some(vj) = v.last_mut() {
+                vj.iter_mut().step_by(3).for_each(|x| *x = -Fr::one());
+            }
+            let v_refs: Vec<&[Fr]> = v.iter().map(Vec::as_slice).collect();
+            let mut z: Vec<Fr> = vec![Fr::one(); num_ops];
+            let mut ark_z = vec![Fr::zero(); num_ops];
+
+            for vj in &v {
+                for (zi, vji) in ark_z.iter_mut().zip(vj) {
+                    *zi += vji;
+                }
+            }
+            sum_vectors_bn254(&v_refs, &mut z);
+            assert_eq!(z, ark_z);
+        }
+
+        let max = vec![-Fr::one(); 9];
+        let v_refs = vec![max.as_slice(); 1_000];
+        let mut z = vec![Fr::zero(); 9];
+        sum_vectors_bn254(&v_refs, &mut z);
+        assert_eq!(z, vec![-Fr::from(1_000u64); 9]);
+    }
+
+    #[test]
+    fn parallel_parity_sum_vectors() {
+        const NUM_OPS: usize = 10_000;
+        const NUM_VECS: usize = 8;
+        let v: Vec<Vec<Fr>> = (0..NUM_VECS).map(|_| rand_vec(NUM_OPS)).collect();
+        let v_refs: Vec<&[Fr]> = v.iter().map(Vec::as_slice).collect();
+        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
+        let mut z_par = z.clone();
+
+        sum_vectors_bn254(&v_refs, &mut z);
+        sum_vectors_par_bn254(&v_refs, &mut z_par);
+        assert_eq!(z, z_par);
+    }
+
+    #[test]
+    fn parallel_parity_vec_add_inplace() {
+        const NUM_OPS: usize = 10_000;
+        let mut x: Vec<Fr> = rand_vec(NUM_OPS);
+        let y: Vec<Fr> = rand_vec(NUM_OPS);
+        let mut x_par = x.clone();
+
+        add_vec_inplace_bn254(&mut x, &y);
+        add_vec_inplace_par_bn254(&mut x_par, &y);
+        assert_eq!(x, x_par);
+    }
+
+    #[test]
+    fn parallel_parity_vec_sub_inplace() {
+        const NUM_OPS: usize = 10_000;
+        let mut x: Vec<Fr> = rand_vec(NUM_OPS);
+        let y: Vec<Fr> = rand_vec(NUM_OPS);
+        let mut x_par = x.clone();
+
+        sub_vec_inplace_bn254(&mut x, &y);
+        sub_vec_inplace_par_bn254(&mut x_par, &y);
+        assert_eq!(x, x_par);
+    }
+
+    #[test]
+    fn parallel_parity_vec_sub_rev_inplace() {
+        const NUM_OPS: usize = 10_000;
+        let x: Vec<Fr> = rand_vec(NUM_OPS);
+        let mut y: Vec<Fr> = rand_vec(NUM_OPS);
+        let mut ark_z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
+        let mut y_par = y.clone();
+
+        ark_batch_sub(&x, &y, &mut ark_z);
+        sub_vec_rev_inplace_bn254(&x, &mut y);
+        sub_vec_rev_inplace_par_bn254(&x, &mut y_par);
+        assert_eq!(y, ark_z);
+        assert_eq!(y, y_par);
+    }
+
+    #[test]
+    fn parallel_parity_vec_mul_inplace() {
+        const NUM_OPS: usize = 10_000;
+        let mut x: Vec<Fr> = rand_vec(NUM_OPS);
+        let y: Vec<Fr> = rand_vec(NUM_OPS);
+        let mut x_par = x.clone();
+
+        mul_vec_inplace_bn254(&mut x, &y);
+        mul_vec_inplace_par_bn254(&mut x_par, &y);
+        assert_eq!(x, x_par);
+    }
+
+    #[test]
+    fn parity_ark_mul_aligned() {
+        const NUM_OPS: usize = 100;
+        let x: Vec<Fr> = rand_vec(NUM_OPS + 1);
+        let y: Vec<Fr> = rand_vec(NUM_OPS + 1);
+        let mut ark_z = vec![Fr::zero(); NUM_OPS + 1];
+        ark_batch_mul(&x, &y, &mut ark_z);
+
+        let ax = FieldVec::from_slice(&x);
+        let ay = FieldVec::from_slice(&y);
+        let mut az = aligned_vec_bn254(NUM_OPS + 1);
+        // Aligned starts, starts one element into a line, and lengths around the block size.
+        for start in [0, 1] {
+            for len in [0, 1, 15, 16, 17, 33, NUM_OPS] {
+                let range = start..start + len;
+                mul_vec_bn254(
+                    &ax[range.clone()],
+                    &ay[range.clone()],
+                    &mut az[range.clone()],
+                );
+                assert_eq!(az[range.clone()], ark_z[range.clone()]);
+
+                let mut x_inplace = ax.clone();
+                mul_vec_inplace_bn254(&mut x_inplace[range.clone()], &ay[range.clone()]);
+                assert_eq!(x_inplace[range.clone()], ark_z[range]);
+            }
+        }
+    }
+
+    #[test]
+    fn parity_ark_nt() {
+        const NUM_OPS: usize = 1_000;
+        let x: Vec<Fr> = rand_vec(NUM_OPS + 1);
+        let y: Vec<Fr> = rand_vec(NUM_OPS + 1);
+        let mut ark_add = vec![Fr::zero(); NUM_OPS + 1];
+        let mut ark_sub = ark_add.clone();
+        let mut ark_mul = ark_add.clone();
+        ark_batch_add(&x, &y, &mut ark_add);
+        ark_batch_sub(&x, &y, &mut ark_sub);
+        ark_batch_mul(&x, &y, &mut ark_mul);
+
+        // Outputs starting on and one element past a 64-byte line, around the block size.
+        let mut z = aligned_vec_bn254(NUM_OPS + 1);
+        for start in [0, 1] {
+            for len in [0, 1, 15, 16, 17, 33, NUM_OPS] {
+                let r = start..start + len;
+                add_vec_nt_bn254(&x[r.clone()], &y[r.clone()], &mut z[r.clone()]);
+                assert_eq!(z[r.clone()], ark_add[r.clone()]);
+                sub_vec_nt_par_bn254(&x[r.clone()], &y[r.clone()], &mut z[r.clone()]);
+                assert_eq!(z[r.clone()], ark_sub[r.clone()]);
+                mul_vec_nt_bn254(&x[r.clone()], &y[r.clone()], &mut z[r.clone()]);
+                assert_eq!(z[r.clone()], ark_mul[r.clone()]);
+                mul_vec_nt_par_bn254(&x[r.clone()], &y[r.clone()], &mut z[r.clone()]);
+                assert_eq!(z[r.clone()], ark_mul[r]);
+            }
+        }
+
+        let _guard = schedule::OVERRIDES_LOCK.lock().unwrap();
+        schedule::set_streaming_min_bytes(Some(0));
+        let mut z_auto = vec![Fr::zero(); NUM_OPS + 1];
+        mul_vec_auto_bn254(&x, &y, &mut z_auto);
+        schedule::set_streaming_min_bytes(None);
+        assert_eq!(z_auto, ark_mul);
+    }
+
+    #[test]
+    fn parity_ark_canonical_conversion() {
+        const NUM_OPS: usize = 2_500;
+        let x: Vec<Fr> = rand_vec(NUM_OPS);
+        let ark_canonical: Vec<[u64; 4]> = x.iter().map(|xi| xi.into_bigint().0).collect();
+
+        let mut canonical = vec![[0u64; 4]; NUM_OPS];
+        montgomery_to_canonical_bn254(&x, &mut canonical);
+        assert_eq!(canonical, ark_canonical);
+
+        let mut montgomery = vec![Fr::zero(); NUM_OPS];
+        canonical_to_montgomery_bn254(&canonical, &mut montgomery);
+        assert_eq!(montgomery, x);
+    }
+}
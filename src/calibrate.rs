@@ -0,0 +1,444 @@
+//! Per-host tuning of the `_par` chunk sizes and thread counts.
+//!
+//! [`calibrate`] times each parallel kernel over a grid of chunk sizes and thread counts, and
+//! [`Calibration::save`] writes the winners to a small text file, one operation per line:
+//!
+//! ```text
+//! # vectorized-fields calibration for c7a-host
+//! mul 16384 16
+//! add 65536 8
+//! ```
+//!
+//! The `schedule` module loads the file for the current host the first time a `_par` function
+//! runs. The location is `$VECTORIZED_FIELDS_CONFIG` if set (an empty value disables loading),
+//! otherwise `calibration-<hostname>.conf` under `$XDG_CONFIG_HOME/vectorized-fields` or
+//! `$HOME/.config/vectorized-fields`.
+
+use std::{
+    fs,
+    hint::black_box,
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    limbs::{self, Modulus},
+    schedule::{self, VecOp},
+};
+
+const CONFIG_ENV: &str = "VECTORIZED_FIELDS_CONFIG";
+
+/// Best measured settings for one operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpSettings {
+    pub op: VecOp,
+    pub chunk_size: usize,
+    pub num_threads: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Calibration {
+    pub host: String,
+    pub settings: Vec<OpSettings>,
+}
+
+/// Grid searched by [`calibrate`].
+#[derive(Clone, Debug)]
+pub struct CalibrationOptions {
+    pub len: usize,
+    pub ops: Vec<VecOp>,
+    pub chunk_sizes: Vec<usize>,
+    pub thread_counts: Vec<usize>,
+    pub repetitions: usize,
+}
+
+impl Default for CalibrationOptions {
+    fn default() -> Self {
+        let max_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let thread_counts = std::iter::successors(Some(1), |t| Some(t * 2))
+            .take_while(|&t| t < max_threads)
+            .chain(std::iter::once(max_threads))
+            .collect();
+
+        Self {
+            len: 1 << 22,
+            ops: vec![
+                VecOp::Add,
+                VecOp::Sub,
+                VecOp::Mul,
+                VecOp::Sum,
+                VecOp::InnerProduct,
+            ],
+            chunk_sizes: (10..=18).step_by(2).map(|log| 1 << log).collect(),
+            thread_counts,
+            repetitions: 3,
+        }
+    }
+}
+
+pub fn host_name() -> String {
+    fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "default".to_string())
+}
+
+/// Location of the calibration file for this host, or `None` if loading is disabled.
+pub fn default_config_path() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var(CONFIG_ENV) {
+        return (!path.is_empty()).then(|| PathBuf::from(path));
+    }
+
+    let dir = match std::env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var("HOME").ok()?).join(".config"),
+    };
+    Some(
+        dir.join("vectorized-fields")
+            .join(format!("calibration-{}.conf", host_name())),
+    )
+}
+
+impl Calibration {
+    pub fn settings(&self, op: VecOp) -> Option<OpSettings> {
+        self.settings.iter().copied().find(|s| s.op == op)
+    }
+
+    /// Installs the settings as `schedule` overrides: each operation then runs with the
+    /// measured chunk size on a pool of the measured thread count.
+    pub fn apply(&self) {
+        for s in &self.settings {
+            schedule::apply_calibrated(s.op, s.chunk_size, s.num_threads);
+        }
+    }
+
+    pub fn to_config_string(&self) -> String {
+        let mut out = format!("# vectorized-fields calibration for {}\n", self.host);
+        for s in &self.settings {
+            out += &format!("{} {} {}\n", s.op.name(), s.chunk_size, s.num_threads);
+        }
+        out
+    }
+
+    pub fn from_config_str(contents: &str) -> io::Result<Self> {
+        let invalid = |line: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid calibration line: {line:?}"),
+            )
+        };
+
+        let mut host = String::new();
+        let mut settings = Vec::new();
+        for line in contents.lines().map(str::trim) {
+            if let Some(comment) = line.strip_prefix('#') {
+                if let Some(name) = comment
+                    .trim()
+                    .strip_prefix("vectorized-fields calibration for ")
+                {
+                    host = name.to_string();
+                }
+                continue;
+            }
+            if line.is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [name, chunk_size, num_threads] = fields[..] else {
+                return Err(invalid(line));
+            };
+            settings.push(OpSettings {
+                op: VecOp::from_name(name).ok_or_else(|| invalid(line))?,
+                chunk_size: chunk_size.parse().map_err(|_| invalid(line))?,
+                num_threads: num_threads.parse().map_err(|_| invalid(line))?,
+            });
+        }
+
+        Ok(Self { host, settings })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_config_string())
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::from_config_str(&fs::read_to_string(path)?)
+    }
+}
+
+/// Applies the calibration file for this host if it exists. Malformed files are ignored.
+pub(crate) fn load_host_calibration() {
+    if let Some(calibration) = default_config_path().and_then(|path| Calibration::load(&path).ok())
+    {
+        calibration.apply();
+    }
+}
+
+/// Vectors combined by the `LinearCombination` and `SumVectors` timings, each `len / 8` elements
+/// long so that every timing reads `len` elements.
+const TIMING_VECTORS: usize = 8;
+
+/// Nonzeros per row of the `Spmv` timing matrix, which has `len / 8` rows and `len` columns.
+#[cfg(feature = "ark")]
+const TIMING_ROW_NNZ: usize = 8;
+
+struct TimingInputs {
+    x: Vec<[u64; 4]>,
+    y: Vec<[u64; 4]>,
+    z: Vec<[u64; 4]>,
+    /// Coefficients of the linear combination, and the point of the polynomial timings.
+    r: Vec<[u64; 4]>,
+    #[cfg(feature = "ark")]
+    matrix: Option<crate::CsrMatrix>,
+}
+
+impl TimingInputs {
+    fn new(options: &CalibrationOptions) -> Self {
+        let len = options.len;
+        Self {
+            x: timing_input(len, 1),
+            y: timing_input(len, 2),
+            z: vec![[0u64; 4]; len],
+            r: timing_input(TIMING_VECTORS, 3),
+            #[cfg(feature = "ark")]
+            matrix: options
+                .ops
+                .contains(&VecOp::Spmv)
+                .then(|| timing_matrix(len)),
+        }
+    }
+}
+
+/// Whether this build has a kernel for `op`. The polynomial and sparse kernels work on arkworks
+/// elements.
+fn has_kernel(op: VecOp) -> bool {
+    cfg!(feature = "ark") || !matches!(op, VecOp::PolyEval | VecOp::DivideByLinear | VecOp::Spmv)
+}
+
+fn time_op(op: VecOp, inputs: &mut TimingInputs, repetitions: usize) -> Duration {
+    let m = &Modulus::BN254_FR;
+    let TimingInputs {
+        x,
+        y,
+        z,
+        r,
+        #[cfg(feature = "ark")]
+        matrix,
+    } = inputs;
+    let n = x.len() / TIMING_VECTORS;
+    let v: Vec<&[[u64; 4]]> = (0..TIMING_VECTORS)
+        .map(|j| &x[j * n..(j + 1) * n])
+        .collect();
+
+    (0..repetitions.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            match op {
+                VecOp::Add => limbs::add_par(m, x, y, z),
+                VecOp::Sub => limbs::sub_par(m, x, y, z),
+                VecOp::Mul => limbs::mul_par(m, x, y, z),
+                VecOp::Sum => {
+                    black_box(limbs::sum_par(m, x));
+                }
+                VecOp::InnerProduct => {
+                    black_box(limbs::inner_product_par(m, x, y));
+                }
+                VecOp::LinearCombination => limbs::linear_combination_par(m, r, &v, &mut z[..n]),
+                VecOp::SumVectors => limbs::sum_vectors_par(m, &v, &mut z[..n]),
+                VecOp::Validate => {
+                    black_box(limbs::validate_par(m, x)).unwrap();
+                }
+                #[cfg(feature = "ark")]
+                VecOp::PolyEval => {
+                    black_box(crate::poly_eval_par_bn254(as_fr(x), as_fr(r)[0]));
+                }
+                #[cfg(feature = "ark")]
+                VecOp::DivideByLinear => {
+                    black_box(crate::divide_by_linear_par_bn254(as_fr(x), as_fr(r)[0]));
+                }
+                #[cfg(feature = "ark")]
+                VecOp::Spmv => {
+                    let matrix = matrix.as_ref().unwrap();
+                    let out = as_fr_mut(&mut z[..matrix.num_rows()]);
+                    crate::spmv_par_bn254(matrix, as_fr(x), out);
+                }
+                #[cfg(not(feature = "ark"))]
+                VecOp::PolyEval | VecOp::DivideByLinear | VecOp::Spmv => {
+                    unreachable!("no {op:?} kernel without the `ark` feature")
+                }
+            }
+            black_box(&z);
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+/// Pseudo-random elements with a zero top limb, hence below the modulus. The kernels take the same
+/// time for any canonical input.
+fn timing_input(len: usize, seed: u64) -> Vec<[u64; 4]> {
+    (0..len as u64)
+        .map(|i| {
+            let h = (i ^ (seed << 32)).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+            [h, h.rotate_left(21), h.rotate_left(42), 0]
+        })
+        .collect()
+}
+
+/// A matrix of `len / TIMING_ROW_NNZ` rows over `len` columns with pseudo-random column indices.
+#[cfg(feature = "ark")]
+fn timing_matrix(len: usize) -> crate::CsrMatrix {
+    let values = timing_input(len, 4);
+    let num_rows = len / TIMING_ROW_NNZ;
+    let entries = (0..num_rows * TIMING_ROW_NNZ)
+        .map(|i| {
+            let col = (i as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) % len as u64;
+            (i / TIMING_ROW_NNZ, col as usize, as_fr(&values)[i])
+        })
+        .collect();
+    crate::CsrMatrix::from_triplets(num_rows, len, entries)
+}
+
+/// Views canonical limbs as `Fr` elements in Montgomery form, which `Fr` is made of. The layout
+/// is checked in `utils`.
+#[cfg(feature = "ark")]
+fn as_fr(x: &[[u64; 4]]) -> &[ark_bn254::Fr] {
+    unsafe { std::slice::from_raw_parts(x.as_ptr() as *const ark_bn254::Fr, x.len()) }
+}
+
+#[cfg(feature = "ark")]
+fn as_fr_mut(x: &mut [[u64; 4]]) -> &mut [ark_bn254::Fr] {
+    unsafe { std::slice::from_raw_parts_mut(x.as_mut_ptr() as *mut ark_bn254::Fr, x.len()) }
+}
+
+/// Times every operation in `options.ops` over the chunk size / thread count grid and returns
+/// the fastest setting for each. Among settings within 5% of the fastest, the one using the
+/// fewest threads wins, leaving spare cores for other work on memory-bound kernels.
+///
+/// `PolyEval`, `DivideByLinear` and `Spmv` have no kernel without the `ark` feature and are left
+/// out of the result in such builds.
+///
+/// The `schedule` overrides of the calibrated operations are left untouched.
+pub fn calibrate(options: &CalibrationOptions) -> Calibration {
+    let mut inputs = TimingInputs::new(options);
+
+    let mut settings = Vec::with_capacity(options.ops.len());
+    for op in options.ops.iter().copied().filter(|&op| has_kernel(op)) {
+        let saved = (
+            schedule::chunk_size_override(op),
+            schedule::max_threads_override(op),
+            schedule::num_threads_override(op),
+        );
+        schedule::set_max_threads(op, None);
+        schedule::set_num_threads(op, None);
+
+        let mut timings = Vec::new();
+        for &num_threads in &options.thread_counts {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .expect("failed to build calibration thread pool");
+            for &chunk_size in &options.chunk_sizes {
+                schedule::set_chunk_size(op, Some(chunk_size));
+                let elapsed = pool.install(|| time_op(op, &mut inputs, options.repetitions));
+                timings.push((elapsed, num_threads, chunk_size));
+            }
+        }
+
+        schedule::set_chunk_size(op, saved.0);
+        schedule::set_max_threads(op, saved.1);
+        schedule::set_num_threads(op, saved.2);
+
+        let fastest = timings.iter().map(|t| t.0).min().unwrap();
+        let (_, num_threads, chunk_size) = timings
+            .into_iter()
+            .filter(|t| t.0.as_secs_f64() <= fastest.as_secs_f64() * 1.05)
+            .min_by_key(|&(elapsed, num_threads, _)| (num_threads, elapsed))
+            .unwrap();
+
+        settings.push(OpSettings {
+            op,
+            chunk_size,
+            num_threads,
+        });
+    }
+
+    Calibration {
+        host: host_name(),
+        settings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calibration_roundtrip() {
+        let calibration = Calibration {
+            host: "test-host".to_string(),
+            settings: vec![
+                OpSettings {
+                    op: VecOp::Mul,
+                    chunk_size: 16_384,
+                    num_threads: 16,
+                },
+                OpSettings {
+                    op: VecOp::Add,
+                    chunk_size: 65_536,
+                    num_threads: 8,
+                },
+            ],
+        };
+
+        let path = std::env::temp_dir().join(format!(
+            "vectorized-fields-calibration-{}.conf",
+            std::process::id()
+        ));
+        calibration.save(&path).unwrap();
+        let loaded = Calibration::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, calibration);
+        assert!(Calibration::from_config_str("mul 12").is_err());
+        assert!(Calibration::from_config_str("div 1 1").is_err());
+    }
+
+    #[test]
+    fn calibrate_small_grid() {
+        let _guard = schedule::OVERRIDES_LOCK.lock().unwrap();
+        let options = CalibrationOptions {
+            len: 1 << 12,
+            ops: VecOp::ALL.to_vec(),
+            chunk_sizes: vec![256, 1024],
+            thread_counts: vec![1, 2],
+            repetitions: 1,
+        };
+
+        let calibration = calibrate(&options);
+        let num_supported = VecOp::ALL.into_iter().filter(|&op| has_kernel(op)).count();
+        assert_eq!(calibration.settings.len(), num_supported);
+        for op in options.ops.iter().copied().filter(|&op| has_kernel(op)) {
+            let s = calibration.settings(op).unwrap();
+            assert!(options.chunk_sizes.contains(&s.chunk_size));
+            assert!(options.thread_counts.contains(&s.num_threads));
+            assert_eq!(schedule::chunk_size_override(op), None);
+        }
+
+        // Shorter than the vectors of the multi-vector timings.
+        for len in [0, 5] {
+            let calibration = calibrate(&CalibrationOptions {
+                len,
+                ..options.clone()
+            });
+            assert_eq!(calibration.settings.len(), num_supported);
+        }
+    }
+}
@@ -0,0 +1,539 @@
+//! C ABI over the vector operations, for consumers in C, C++, Go and the like.
+//!
+//! Every function takes the field as a `VF_FIELD_*` constant and, where it matters, a
+//! `VF_MODE_*` constant choosing the serial, parallel or automatic kernel. Elements are passed as
+//! pointers to `LIMBS` little-endian `uint64_t` limbs in Montgomery form (4 for the BN254 fields) and
+//! lengths count elements. Instead of panicking, functions return a `vf_status`: `VF_OK` or one
+//! of the `VF_ERR_*` codes, in which case outputs are left unspecified.
+//!
+//! The fields are the two 4-limb BN254 fields. BLS12-381 `Fq`, whose 6-limb kernels have no
+//! range check, `_auto` or multi-vector variants yet, is only available from Rust.
+//!
+//! Pointers may be null only when the length is zero, must be aligned to 8 bytes and, except for
+//! the `_inplace` functions, outputs must not overlap inputs. Inputs are checked to be canonical
+//! residues on every call, since the kernels silently misbehave on anything else.
+//!
+//! `include/vectorized_fields.h` is generated from this file by `cargo run -- header`.
+
+// The safety contract is the same for every function and is spelled out above and in the header.
+#![allow(clippy::missing_safety_doc)]
+
+use std::{
+    fmt::Write,
+    mem,
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use crate::{
+    limbs::{self, Modulus},
+    schedule::{self, VecOp},
+};
+
+/// Defines the exported constants and records them for [`c_header`].
+macro_rules! c_constants {
+    ($(
+        $(#[doc = $doc:literal])*
+        $name:ident: $ty:ty = $value:expr;
+    )*) => {
+        $(
+            $(#[doc = $doc])*
+            pub const $name: $ty = $value;
+        )*
+
+        const CONSTANTS: &[(&str, &[&str], i64)] = &[$((stringify!($name), &[$($doc),*], $name as i64)),*];
+    };
+}
+
+/// Defines the exported functions, each returning a `vf_status`, and records their prototypes
+/// for [`c_header`]. Bodies evaluate to `Result<(), i32>`; panics become `VF_ERR_PANIC`.
+macro_rules! c_functions {
+    ($(
+        $(#[doc = $doc:literal])*
+        fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $body:block
+    )*) => {
+        $(
+            $(#[doc = $doc])*
+            #[no_mangle]
+            pub unsafe extern "C" fn $name($($arg: $ty),*) -> i32 {
+                guard(|| $body)
+            }
+        )*
+
+        const FUNCTIONS: &[(&str, &[&str], &[(&str, &str)])] = &[
+            $((stringify!($name), &[$($doc),*], &[$((stringify!($arg), stringify!($ty))),*])),*
+        ];
+    };
+}
+
+c_constants! {
+    /// The BN254 scalar field, 4 limbs per element.
+    VF_FIELD_BN254_FR: u32 = 0;
+    /// The BN254 base field, 4 limbs per element.
+    VF_FIELD_BN254_FQ: u32 = 1;
+
+    /// Run on the calling thread.
+    VF_MODE_SERIAL: u32 = 0;
+    /// Split across the global rayon pool.
+    VF_MODE_PARALLEL: u32 = 1;
+    /// Pick serial, parallel or streaming stores from the length, as the `_auto` functions do.
+    VF_MODE_AUTO: u32 = 2;
+
+    /// Success.
+    VF_OK: i32 = 0;
+    /// A pointer was null with a non-zero length.
+    VF_ERR_NULL_POINTER: i32 = 1;
+    /// A pointer was not aligned to 8 bytes.
+    VF_ERR_MISALIGNED: i32 = 2;
+    /// The field is not one of the `VF_FIELD_*` constants.
+    VF_ERR_UNKNOWN_FIELD: i32 = 3;
+    /// The mode is not one of the `VF_MODE_*` constants.
+    VF_ERR_UNKNOWN_MODE: i32 = 4;
+    /// An output overlaps an input.
+    VF_ERR_OVERLAP: i32 = 5;
+    /// The length does not fit in memory.
+    VF_ERR_LENGTH: i32 = 6;
+    /// An input element is not below the modulus.
+    VF_ERR_NON_CANONICAL: i32 = 7;
+    /// An internal error; please report it.
+    VF_ERR_PANIC: i32 = 8;
+}
+
+type Status = i32;
+
+type Limbs = [u64; 4];
+
+fn parse_field(field: u32) -> Result<&'static Modulus, Status> {
+    match field {
+        VF_FIELD_BN254_FR => Ok(&Modulus::BN254_FR),
+        VF_FIELD_BN254_FQ => Ok(&Modulus::BN254_FQ),
+        _ => Err(VF_ERR_UNKNOWN_FIELD),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Mode {
+    Serial,
+    Parallel,
+    Auto,
+}
+
+impl Mode {
+    fn parallel(self, op: VecOp, len: usize) -> bool {
+        match self {
+            Mode::Serial => false,
+            Mode::Parallel => true,
+            Mode::Auto => schedule::should_parallelize(op, len),
+        }
+    }
+
+    /// Picks the serial, parallel or `_auto` function.
+    fn pick<T>(self, [serial, par, auto]: [T; 3]) -> T {
+        match self {
+            Mode::Serial => serial,
+            Mode::Parallel => par,
+            Mode::Auto => auto,
+        }
+    }
+}
+
+fn parse_mode(mode: u32) -> Result<Mode, Status> {
+    match mode {
+        VF_MODE_SERIAL => Ok(Mode::Serial),
+        VF_MODE_PARALLEL => Ok(Mode::Parallel),
+        VF_MODE_AUTO => Ok(Mode::Auto),
+        _ => Err(VF_ERR_UNKNOWN_MODE),
+    }
+}
+
+fn guard(f: impl FnOnce() -> Result<(), Status>) -> Status {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => VF_OK,
+        Ok(Err(status)) => status,
+        Err(_) => VF_ERR_PANIC,
+    }
+}
+
+/// Checks that `len` elements of `T` at `ptr` can form a slice.
+fn check_ptr<T>(ptr: *const u64, len: usize) -> Result<(), Status> {
+    if len == 0 {
+        return Ok(());
+    }
+    if ptr.is_null() {
+        return Err(VF_ERR_NULL_POINTER);
+    }
+    if !(ptr as usize).is_multiple_of(mem::align_of::<T>()) {
+        return Err(VF_ERR_MISALIGNED);
+    }
+    match len.checked_mul(mem::size_of::<T>()) {
+        Some(bytes) if bytes <= isize::MAX as usize => Ok(()),
+        _ => Err(VF_ERR_LENGTH),
+    }
+}
+
+unsafe fn elements<'a, T>(ptr: *const u64, len: usize) -> Result<&'a [T], Status> {
+    check_ptr::<T>(ptr, len)?;
+    if len == 0 {
+        return Ok(&[]);
+    }
+    Ok(slice::from_raw_parts(ptr as *const T, len))
+}
+
+/// Like [`elements`], after checking that the output does not overlap any of `inputs`.
+unsafe fn elements_mut<'a, T>(
+    ptr: *mut u64,
+    len: usize,
+    inputs: &[&[T]],
+) -> Result<&'a mut [T], Status> {
+    check_ptr::<T>(ptr, len)?;
+    if len == 0 {
+        return Ok(&mut []);
+    }
+    let out = ptr as usize..ptr as usize + len * mem::size_of::<T>();
+    for input in inputs {
+        let range = input.as_ptr_range();
+        if (range.start as usize) < out.end && out.start < range.end as usize {
+            return Err(VF_ERR_OVERLAP);
+        }
+    }
+    Ok(slice::from_raw_parts_mut(ptr as *mut T, len))
+}
+
+fn canonical(m: &Modulus, x: &[Limbs], mode: Mode) -> Result<(), Status> {
+    let valid = if mode.parallel(VecOp::Validate, x.len()) {
+        limbs::validate_par(m, x)
+    } else {
+        limbs::validate(m, x)
+    };
+    valid.map_err(|_| VF_ERR_NON_CANONICAL)
+}
+
+type Binary = fn(&Modulus, &[Limbs], &[Limbs], &mut [Limbs]);
+type BinaryInplace = fn(&Modulus, &mut [Limbs], &[Limbs]);
+
+/// `z = op(x, y)` for the elementwise operations.
+unsafe fn binary(
+    field: u32,
+    mode: u32,
+    z: *mut u64,
+    x: *const u64,
+    y: *const u64,
+    len: usize,
+    ops: [Binary; 3],
+) -> Result<(), Status> {
+    let mode = parse_mode(mode)?;
+    let m = parse_field(field)?;
+    let (x, y) = (elements::<Limbs>(x, len)?, elements::<Limbs>(y, len)?);
+    canonical(m, x, mode)?;
+    canonical(m, y, mode)?;
+    mode.pick(ops)(m, x, y, elements_mut(z, len, &[x, y])?);
+    Ok(())
+}
+
+/// `x = op(x, y)` for the in-place elementwise operations.
+unsafe fn binary_inplace(
+    field: u32,
+    mode: u32,
+    x: *mut u64,
+    y: *const u64,
+    len: usize,
+    ops: [BinaryInplace; 3],
+) -> Result<(), Status> {
+    let mode = parse_mode(mode)?;
+    let m = parse_field(field)?;
+    let y = elements::<Limbs>(y, len)?;
+    canonical(m, elements::<Limbs>(x, len)?, mode)?;
+    canonical(m, y, mode)?;
+    mode.pick(ops)(m, elements_mut(x, len, &[y])?, y);
+    Ok(())
+}
+
+/// The `k` vectors of `len` elements behind `v`.
+unsafe fn vectors<'a>(
+    m: &Modulus,
+    v: *const *const u64,
+    k: usize,
+    len: usize,
+    mode: Mode,
+) -> Result<Vec<&'a [Limbs]>, Status> {
+    let pointers = elements::<*const u64>(v as *const u64, k)?;
+    let vectors = pointers
+        .iter()
+        .map(|&vj| elements::<Limbs>(vj, len))
+        .collect::<Result<Vec<_>, _>>()?;
+    for vj in &vectors {
+        canonical(m, vj, mode)?;
+    }
+    Ok(vectors)
+}
+
+c_functions! {
+    /// Writes the number of 64-bit limbs per element of `field` to `limbs`.
+    fn vf_field_limbs(field: u32, limbs: *mut usize) {
+        parse_field(field)?;
+        check_ptr::<usize>(limbs as *const u64, 1)?;
+        ptr::write(limbs, 4);
+        Ok(())
+    }
+
+    /// Checks that the `len` elements of `x` are below the modulus. On `VF_ERR_NON_CANONICAL`,
+    /// writes the index of the first one that is not to `index` unless it is null.
+    fn vf_validate_canonical(field: u32, mode: u32, x: *const u64, len: usize, index: *mut usize) {
+        let mode = parse_mode(mode)?;
+        let m = parse_field(field)?;
+        let x = elements::<Limbs>(x, len)?;
+        let valid = if mode.parallel(VecOp::Validate, len) {
+            limbs::validate_par(m, x)
+        } else {
+            limbs::validate(m, x)
+        };
+        if let Err(i) = valid {
+            if !index.is_null() {
+                check_ptr::<usize>(index as *const u64, 1)?;
+                ptr::write(index, i);
+            }
+            return Err(VF_ERR_NON_CANONICAL);
+        }
+        Ok(())
+    }
+
+    /// `z = x + y`, elementwise.
+    fn vf_add(field: u32, mode: u32, z: *mut u64, x: *const u64, y: *const u64, len: usize) {
+        binary(field, mode, z, x, y, len, [limbs::add, limbs::add_par, limbs::add_auto])
+    }
+
+    /// `z = x - y`, elementwise.
+    fn vf_sub(field: u32, mode: u32, z: *mut u64, x: *const u64, y: *const u64, len: usize) {
+        binary(field, mode, z, x, y, len, [limbs::sub, limbs::sub_par, limbs::sub_auto])
+    }
+
+    /// `z = x * y`, elementwise.
+    fn vf_mul(field: u32, mode: u32, z: *mut u64, x: *const u64, y: *const u64, len: usize) {
+        binary(field, mode, z, x, y, len, [limbs::mul, limbs::mul_par, limbs::mul_auto])
+    }
+
+    /// `z = x + y`, elementwise, written with non-temporal stores that skip the cache. For outputs
+    /// far larger than the last-level cache that are not read again soon.
+    fn vf_add_nt(field: u32, mode: u32, z: *mut u64, x: *const u64, y: *const u64, len: usize) {
+        binary(field, mode, z, x, y, len, [limbs::add_nt, limbs::add_nt_par, limbs::add_auto])
+    }
+
+    /// `z = x - y`, elementwise, with non-temporal stores; see `vf_add_nt`.
+    fn vf_sub_nt(field: u32, mode: u32, z: *mut u64, x: *const u64, y: *const u64, len: usize) {
+        binary(field, mode, z, x, y, len, [limbs::sub_nt, limbs::sub_nt_par, limbs::sub_auto])
+    }
+
+    /// `z = x * y`, elementwise, with non-temporal stores; see `vf_add_nt`.
+    fn vf_mul_nt(field: u32, mode: u32, z: *mut u64, x: *const u64, y: *const u64, len: usize) {
+        binary(field, mode, z, x, y, len, [limbs::mul_nt, limbs::mul_nt_par, limbs::mul_auto])
+    }
+
+    /// `x = x + y`, elementwise.
+    fn vf_add_inplace(field: u32, mode: u32, x: *mut u64, y: *const u64, len: usize) {
+        binary_inplace(field, mode, x, y, len, [limbs::add_inplace, limbs::add_inplace_par, limbs::add_inplace_auto])
+    }
+
+    /// `x = x - y`, elementwise.
+    fn vf_sub_inplace(field: u32, mode: u32, x: *mut u64, y: *const u64, len: usize) {
+        binary_inplace(field, mode, x, y, len, [limbs::sub_inplace, limbs::sub_inplace_par, limbs::sub_inplace_auto])
+    }
+
+    /// `y = x - y`, elementwise.
+    fn vf_sub_rev_inplace(field: u32, mode: u32, x: *const u64, y: *mut u64, len: usize) {
+        let mode = parse_mode(mode)?;
+        let m = parse_field(field)?;
+        let x = elements::<Limbs>(x, len)?;
+        canonical(m, x, mode)?;
+        canonical(m, elements::<Limbs>(y, len)?, mode)?;
+        let y = elements_mut(y, len, &[x])?;
+        if mode.parallel(VecOp::Sub, len) {
+            limbs::sub_rev_inplace_par(m, x, y)
+        } else {
+            limbs::sub_rev_inplace(m, x, y)
+        }
+        Ok(())
+    }
+
+    /// `x = x * y`, elementwise.
+    fn vf_mul_inplace(field: u32, mode: u32, x: *mut u64, y: *const u64, len: usize) {
+        binary_inplace(field, mode, x, y, len, [limbs::mul_inplace, limbs::mul_inplace_par, limbs::mul_inplace_auto])
+    }
+
+    /// Writes the sum of the `len` elements of `x` to the element at `out`.
+    fn vf_sum(field: u32, mode: u32, out: *mut u64, x: *const u64, len: usize) {
+        let mode = parse_mode(mode)?;
+        let m = parse_field(field)?;
+        check_ptr::<Limbs>(out, 1)?;
+        let x = elements::<Limbs>(x, len)?;
+        canonical(m, x, mode)?;
+        let sum = mode.pick([limbs::sum, limbs::sum_par, limbs::sum_auto])(m, x);
+        ptr::write(out as *mut Limbs, sum);
+        Ok(())
+    }
+
+    /// Writes the inner product of `x` and `y` to the element at `out`.
+    fn vf_inner_product(field: u32, mode: u32, out: *mut u64, x: *const u64, y: *const u64, len: usize) {
+        let mode = parse_mode(mode)?;
+        let m = parse_field(field)?;
+        check_ptr::<Limbs>(out, 1)?;
+        let (x, y) = (elements::<Limbs>(x, len)?, elements::<Limbs>(y, len)?);
+        canonical(m, x, mode)?;
+        canonical(m, y, mode)?;
+        let ops = [limbs::inner_product, limbs::inner_product_par, limbs::inner_product_auto];
+        ptr::write(out as *mut Limbs, mode.pick(ops)(m, x, y));
+        Ok(())
+    }
+
+    /// `z = r[0] * v[0] + ... + r[k - 1] * v[k - 1]` for `k` vectors `v[j]` of `len` elements.
+    fn vf_linear_combination(field: u32, mode: u32, z: *mut u64, r: *const u64, v: *const *const u64, k: usize, len: usize) {
+        let mode = parse_mode(mode)?;
+        let m = parse_field(field)?;
+        let r = elements::<Limbs>(r, k)?;
+        canonical(m, r, mode)?;
+        let v = vectors(m, v, k, len, mode)?;
+        let mut inputs = v.clone();
+        inputs.push(r);
+        let z = elements_mut(z, len, &inputs)?;
+        if mode.parallel(VecOp::LinearCombination, len * k) {
+            limbs::linear_combination_par(m, r, &v, z)
+        } else {
+            limbs::linear_combination(m, r, &v, z)
+        }
+        Ok(())
+    }
+
+    /// `z = v[0] + ... + v[k - 1]` for `k` vectors `v[j]` of `len` elements.
+    fn vf_sum_vectors(field: u32, mode: u32, z: *mut u64, v: *const *const u64, k: usize, len: usize) {
+        let mode = parse_mode(mode)?;
+        let m = parse_field(field)?;
+        let v = vectors(m, v, k, len, mode)?;
+        let z = elements_mut(z, len, &v)?;
+        if mode.parallel(VecOp::SumVectors, len * k) {
+            limbs::sum_vectors_par(m, &v, z)
+        } else {
+            limbs::sum_vectors(m, &v, z)
+        }
+        Ok(())
+    }
+
+    /// Converts `len` Montgomery-form elements `x` to canonical little-endian limbs in `z`.
+    fn vf_to_canonical(field: u32, mode: u32, z: *mut u64, x: *const u64, len: usize) {
+        let mode = parse_mode(mode)?;
+        let m = parse_field(field)?;
+        let x = elements::<Limbs>(x, len)?;
+        canonical(m, x, mode)?;
+        let z = elements_mut(z, len, &[x])?;
+        if mode.parallel(VecOp::Mul, len) {
+            limbs::from_montgomery_par(m, x, z)
+        } else {
+            limbs::from_montgomery(m, x, z)
+        }
+        Ok(())
+    }
+
+    /// Converts `len` canonical little-endian limbs `x` to Montgomery-form elements in `z`.
+    fn vf_from_canonical(field: u32, mode: u32, z: *mut u64, x: *const u64, len: usize) {
+        let mode = parse_mode(mode)?;
+        let m = parse_field(field)?;
+        let x = elements::<Limbs>(x, len)?;
+        canonical(m, x, mode)?;
+        let z = elements_mut(z, len, &[x])?;
+        if mode.parallel(VecOp::Mul, len) {
+            limbs::to_montgomery_par(m, x, z)
+        } else {
+            limbs::to_montgomery(m, x, z)
+        }
+        Ok(())
+    }
+}
+
+/// C spelling of a Rust parameter type used above.
+fn c_type(ty: &str) -> &'static str {
+    match ty.split_whitespace().collect::<String>().as_str() {
+        "u32" => "uint32_t",
+        "usize" => "size_t",
+        "*constu64" => "const uint64_t *",
+        "*mutu64" => "uint64_t *",
+        "*const*constu64" => "const uint64_t *const *",
+        "*mutusize" => "size_t *",
+        other => panic!("no C type for {other}"),
+    }
+}
+
+fn c_comment(out: &mut String, docs: &[&str]) {
+    for line in docs {
+        writeln!(out, "//{line}").unwrap();
+    }
+}
+
+/// The C header declaring everything in this module, as checked in at
+/// `include/vectorized_fields.h`.
+pub fn c_header() -> String {
+    let mut out = String::new();
+    out.push_str(
+        "// Generated by `cargo run -- header` from src/capi.rs; do not edit.\n\
+         //\n\
+         // Elements are arrays of little-endian uint64_t limbs in Montgomery form and lengths\n\
+         // count elements. Pointers may be null only with a zero length, must be 8-byte\n\
+         // aligned and, except for the _inplace functions, outputs must not overlap inputs.\n\
+         // Every function returns VF_OK or a VF_ERR_* code, leaving outputs unspecified on error.\n\
+         // Only the BN254 fields are exported; BLS12-381 Fq is only available from Rust.\n\
+         \n\
+         #ifndef VECTORIZED_FIELDS_H\n\
+         #define VECTORIZED_FIELDS_H\n\
+         \n\
+         #include <stddef.h>\n\
+         #include <stdint.h>\n\
+         \n\
+         #ifdef __cplusplus\n\
+         extern \"C\" {\n\
+         #endif\n\
+         \n\
+         typedef int32_t vf_status;\n",
+    );
+
+    for (name, docs, value) in CONSTANTS {
+        out.push('\n');
+        c_comment(&mut out, docs);
+        writeln!(out, "#define {name} {value}").unwrap();
+    }
+
+    for (name, docs, args) in FUNCTIONS {
+        out.push('\n');
+        c_comment(&mut out, docs);
+        let args: Vec<String> = args
+            .iter()
+            .map(|(arg, ty)| {
+                let ty = c_type(ty);
+                let sep = if ty.ends_with('*') { "" } else { " " };
+                format!("{ty}{sep}{arg}")
+            })
+            .collect();
+        writeln!(out, "vf_status {name}({});", args.join(", ")).unwrap();
+    }
+
+    out.push_str(
+        "\n\
+         #ifdef __cplusplus\n\
+         }\n\
+         #endif\n\
+         \n\
+         #endif\n",
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn c_header_is_current() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/include/vectorized_fields.h");
+        let checked_in = std::fs::read_to_string(path).unwrap_or_default();
+        assert!(
+            checked_in == c_header(),
+            "{path} is out of date; regenerate it with `cargo run -- header > {path}`"
+        );
+    }
+}
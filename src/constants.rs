@@ -23,3 +23,20 @@ pub const BN254_FR: [u64; 6] = [
     // For Barrett: mu = 2^288 / m
     0x000000054A474626,
 ];
+
+/// The BN254 Fr modulus alone, the first four words of [`BN254_FR`].
+pub const BN254_FR_MODULUS: [u64; 4] = [BN254_FR[0], BN254_FR[1], BN254_FR[2], BN254_FR[3]];
+
+/// The BN254 Fq modulus alone, the first four words of [`BN254_FP`].
+pub const BN254_FQ_MODULUS: [u64; 4] = [BN254_FP[0], BN254_FP[1], BN254_FP[2], BN254_FP[3]];
+
+/// The BLS12-381 base field modulus:
+/// 0x1A0111EA397FE69A4B1BA7B6434BACD764774B84F38512BF6730D2A0F6B0F6241EABFFFEB153FFFFB9FEFFFFFFFFAAAB
+pub const BLS12_381_FQ_MODULUS: [u64; 6] = [
+    0xb9feffffffffaaab,
+    0x1eabfffeb153ffff,
+    0x6730d2a0f6b0f624,
+    0x64774b84f38512bf,
+    0x4b1ba7b6434bacd7,
+    0x1a0111ea397fe69a,
+];
@@ -0,0 +1,186 @@
+use ark_bls12_381::Fq as Fq381;
+use ark_bn254::{Fq, Fr};
+use rayon::ThreadPool;
+
+use crate::{schedule, CsrMatrix};
+
+/// Runs the parallel functions of every field on a caller-supplied rayon pool instead of the
+/// global one.
+///
+/// Chunk sizes are derived from the pool's thread count, unless the context fixes its own with
+/// [`ParContext::with_chunk_size`]. Contexts over different pools can run concurrently without
+/// sharing workers.
+#[derive(Clone, Copy)]
+pub struct ParContext<'a> {
+    pool: &'a ThreadPool,
+    chunk_size: Option<usize>,
+}
+
+macro_rules! forward_par {
+    ($($name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)?;)*) => {
+        $(
+            pub fn $name(&self, $($arg: $ty),*) $(-> $ret)? {
+                self.install(|| crate::$name($($arg),*))
+            }
+        )*
+    };
+}
+
+impl<'a> ParContext<'a> {
+    pub fn new(pool: &'a ThreadPool) -> Self {
+        Self {
+            pool,
+            chunk_size: None,
+        }
+    }
+
+    /// Uses `chunk_size` for every operation run through this context.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = Some(chunk_size.max(1));
+        self
+    }
+
+    pub fn pool(&self) -> &'a ThreadPool {
+        self.pool
+    }
+
+    pub fn chunk_size(&self) -> Option<usize> {
+        self.chunk_size
+    }
+
+    /// Runs `f` inside the pool with this context's chunking policy, e.g. to evaluate an
+    /// `expr` tree or `FieldVec` arithmetic on the pool.
+    pub fn install<R: Send>(&self, f: impl FnOnce() -> R + Send) -> R {
+        let chunk_size = self.chunk_size;
+        self.pool
+            .install(|| schedule::with_chunk_size(chunk_size, f))
+    }
+
+    forward_par! {
+        add_vec_par_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]);
+        add_vec_inplace_par_bn254(x: &mut [Fr], y: &[Fr]);
+        add_vec_auto_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]);
+        add_vec_nt_par_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]);
+        add_vec_inplace_auto_bn254(x: &mut [Fr], y: &[Fr]);
+        sub_vec_par_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]);
+        sub_vec_inplace_par_bn254(x: &mut [Fr], y: &[Fr]);
+        sub_vec_rev_inplace_par_bn254(x: &[Fr], y: &mut [Fr]);
+        sub_vec_auto_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]);
+        sub_vec_nt_par_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]);
+        sub_vec_inplace_auto_bn254(x: &mut [Fr], y: &[Fr]);
+        mul_vec_par_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]);
+        mul_vec_inplace_par_bn254(x: &mut [Fr], y: &[Fr]);
+        mul_vec_auto_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]);
+        mul_vec_nt_par_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]);
+        mul_vec_inplace_auto_bn254(x: &mut [Fr], y: &[Fr]);
+        sum_vec_par_bn254(x: &[Fr]) -> Fr;
+        sum_vec_auto_bn254(x: &[Fr]) -> Fr;
+        inner_product_par_bn254(x: &[Fr], y: &[Fr]) -> Fr;
+        inner_product_auto_bn254(x: &[Fr], y: &[Fr]) -> Fr;
+        montgomery_to_canonical_par_bn254(x: &[Fr], z: &mut [[u64; 4]]);
+        canonical_to_montgomery_par_bn254(x: &[[u64; 4]], z: &mut [Fr]);
+        validate_canonical_par_bn254(x: &[Fr]) -> Result<(), usize>;
+        linear_combination_par_bn254(r: &[Fr], v: &[&[Fr]], z: &mut [Fr]);
+        sum_vectors_par_bn254(v: &[&[Fr]], z: &mut [Fr]);
+        spmv_par_bn254(m: &CsrMatrix, z: &[Fr], out: &mut [Fr]);
+        poly_eval_par_bn254(coeffs: &[Fr], z: Fr) -> Fr;
+        divide_by_linear_par_bn254(coeffs: &[Fr], a: Fr) -> (Vec<Fr>, Fr);
+        divide_many_by_linear_bn254(polys: &[&[Fr]], a: Fr) -> Vec<(Vec<Fr>, Fr)>;
+
+        add_vec_par_bn254_fq(x: &[Fq], y: &[Fq], z: &mut [Fq]);
+        add_vec_inplace_par_bn254_fq(x: &mut [Fq], y: &[Fq]);
+        sub_vec_par_bn254_fq(x: &[Fq], y: &[Fq], z: &mut [Fq]);
+        sub_vec_inplace_par_bn254_fq(x: &mut [Fq], y: &[Fq]);
+        mul_vec_par_bn254_fq(x: &[Fq], y: &[Fq], z: &mut [Fq]);
+        mul_vec_inplace_par_bn254_fq(x: &mut [Fq], y: &[Fq]);
+        sum_vec_par_bn254_fq(x: &[Fq]) -> Fq;
+        inner_product_par_bn254_fq(x: &[Fq], y: &[Fq]) -> Fq;
+
+        add_vec_par_bls12_381_fq(x: &[Fq381], y: &[Fq381], z: &mut [Fq381]);
+        add_vec_inplace_par_bls12_381_fq(x: &mut [Fq381], y: &[Fq381]);
+        sub_vec_par_bls12_381_fq(x: &[Fq381], y: &[Fq381], z: &mut [Fq381]);
+        sub_vec_inplace_par_bls12_381_fq(x: &mut [Fq381], y: &[Fq381]);
+        mul_vec_par_bls12_381_fq(x: &[Fq381], y: &[Fq381], z: &mut [Fq381]);
+        mul_vec_inplace_par_bls12_381_fq(x: &mut [Fq381], y: &[Fq381]);
+        sum_vec_par_bls12_381_fq(x: &[Fq381]) -> Fq381;
+        inner_product_par_bls12_381_fq(x: &[Fq381], y: &[Fq381]) -> Fq381;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::rand_vec;
+    use crate::{
+        inner_product_bls12_381_fq, inner_product_bn254, inner_product_bn254_fq,
+        montgomery_to_canonical_bn254, mul_vec_bls12_381_fq, mul_vec_bn254, mul_vec_bn254_fq,
+        schedule::VecOp,
+    };
+    use ark_std::Zero;
+
+    #[test]
+    fn parity_par_context() {
+        const NUM_OPS: usize = 10_000;
+        let x: Vec<Fr> = rand_vec(NUM_OPS);
+        let y: Vec<Fr> = rand_vec(NUM_OPS);
+        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
+        let mut z_ctx = z.clone();
+        mul_vec_bn254(&x, &y, &mut z);
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let ctx = ParContext::new(&pool);
+        ctx.mul_vec_par_bn254(&x, &y, &mut z_ctx);
+        assert_eq!(z, z_ctx);
+        assert_eq!(ctx.install(rayon::current_num_threads), 2);
+
+        let ctx = ctx.with_chunk_size(100);
+        assert_eq!(
+            ctx.install(|| schedule::chunk_size(VecOp::Mul, NUM_OPS)),
+            100
+        );
+        assert_eq!(
+            ctx.inner_product_par_bn254(&x, &y),
+            inner_product_bn254(&x, &y)
+        );
+        z_ctx.fill(Fr::zero());
+        ctx.mul_vec_nt_par_bn254(&x, &y, &mut z_ctx);
+        assert_eq!(z, z_ctx);
+
+        let mut canonical = vec![[0; 4]; NUM_OPS];
+        let mut canonical_ctx = canonical.clone();
+        montgomery_to_canonical_bn254(&x, &mut canonical);
+        ctx.montgomery_to_canonical_par_bn254(&x, &mut canonical_ctx);
+        assert_eq!(canonical, canonical_ctx);
+        ctx.canonical_to_montgomery_par_bn254(&canonical_ctx, &mut z_ctx);
+        assert_eq!(x, z_ctx);
+
+        assert_eq!(ctx.validate_canonical_par_bn254(&x), Ok(()));
+
+        let x: Vec<Fq> = rand_vec(NUM_OPS);
+        let y: Vec<Fq> = rand_vec(NUM_OPS);
+        let mut z = vec![Fq::zero(); NUM_OPS];
+        let mut z_ctx = z.clone();
+        mul_vec_bn254_fq(&x, &y, &mut z);
+        ctx.mul_vec_par_bn254_fq(&x, &y, &mut z_ctx);
+        assert_eq!(z, z_ctx);
+        assert_eq!(
+            ctx.inner_product_par_bn254_fq(&x, &y),
+            inner_product_bn254_fq(&x, &y)
+        );
+
+        let x: Vec<Fq381> = rand_vec(NUM_OPS);
+        let y: Vec<Fq381> = rand_vec(NUM_OPS);
+        let mut z = vec![Fq381::zero(); NUM_OPS];
+        let mut z_ctx = z.clone();
+        mul_vec_bls12_381_fq(&x, &y, &mut z);
+        ctx.mul_vec_par_bls12_381_fq(&x, &y, &mut z_ctx);
+        assert_eq!(z, z_ctx);
+        assert_eq!(
+            ctx.inner_product_par_bls12_381_fq(&x, &y),
+            inner_product_bls12_381_fq(&x, &y)
+        );
+    }
+}
@@ -0,0 +1,341 @@
+//! Lazy elementwise expressions that are evaluated in cache-sized blocks.
+//!
+//! ```text
+//! use vectorized_fields::expr::var;
+//!
+//! // eq * (a * b - c), summed, in one pass over memory.
+//! let claim = (var(&eq) * (var(&a) * var(&b) - var(&c))).sum();
+//! ```
+//!
+//! Every operator of the tree runs on the existing vector kernels, but only over one block of
+//! [`EXPR_BLOCK_SIZE`] elements at a time, so intermediates stay in L1/L2 instead of being
+//! written out as full-length vectors. The parallel forms hand each rayon task a run of whole
+//! blocks sized by `schedule`.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::ops::{Add, Mul, Range, Sub};
+
+use crate::{
+    field::{zeroed_vec, VectorizedField},
+    par::*,
+    schedule::{self, VecOp},
+};
+
+/// Number of elements evaluated per block, 16 KiB of 256-bit elements per temporary.
+pub const EXPR_BLOCK_SIZE: usize = 512;
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+}
+
+#[derive(Clone, Debug)]
+enum Node<'a, F> {
+    Var(&'a [F]),
+    Const(F),
+    Binary(Op, Box<Node<'a, F>>, Box<Node<'a, F>>),
+}
+
+/// An unevaluated elementwise expression over borrowed vectors.
+#[derive(Clone, Debug)]
+pub struct Expr<'a, F> {
+    node: Node<'a, F>,
+    len: Option<usize>,
+}
+
+/// Leaf referencing a vector.
+pub fn var<F: VectorizedField>(x: &[F]) -> Expr<'_, F> {
+    Expr {
+        node: Node::Var(x),
+        len: Some(x.len()),
+    }
+}
+
+/// Leaf broadcasting a scalar to every index.
+pub fn constant<'a, F: VectorizedField>(c: F) -> Expr<'a, F> {
+    Expr {
+        node: Node::Const(c),
+        len: None,
+    }
+}
+
+impl<'a, F: VectorizedField> Node<'a, F> {
+    /// Number of block-sized temporaries needed to evaluate this node.
+    fn depth(&self) -> usize {
+        match self {
+            Node::Var(_) | Node::Const(_) => 0,
+            Node::Binary(_, l, r) => 1 + core::cmp::max(l.depth(), r.depth()),
+        }
+    }
+
+    /// Number of operators in this node, each costing at most a `Mul` per element.
+    fn num_ops(&self) -> usize {
+        match self {
+            Node::Var(_) | Node::Const(_) => 0,
+            Node::Binary(_, l, r) => 1 + l.num_ops() + r.num_ops(),
+        }
+    }
+
+    /// Writes elements `start..start + out.len()` of this node into `out`.
+    fn eval_block(&self, start: usize, out: &mut [F], scratch: &mut [Vec<F>]) {
+        let end = start + out.len();
+        match self {
+            Node::Var(x) => out.copy_from_slice(&x[start..end]),
+            Node::Const(c) => out.fill(*c),
+            Node::Binary(op, l, r) => {
+                let (tmp, rest) = scratch.split_first_mut().unwrap();
+                let tmp = &mut tmp[..out.len()];
+
+                let rhs: &[F] = match r.as_ref() {
+                    Node::Var(y) => &y[start..end],
+                    _ => {
+                        r.eval_block(start, tmp, rest);
+                        tmp
+                    }
+                };
+
+                match l.as_ref() {
+                    Node::Var(x) => {
+                        let x = &x[start..end];
+                        match op {
+                            Op::Add => F::add_vec(x, rhs, out),
+                            Op::Sub => F::sub_vec(x, rhs, out),
+                            Op::Mul => F::mul_vec(x, rhs, out),
+                        }
+                    }
+                    _ => {
+                        l.eval_block(start, out, rest);
+                        match op {
+                            Op::Add => F::add_vec_inplace(out, rhs),
+                            Op::Sub => F::sub_vec_inplace(out, rhs),
+                            Op::Mul => F::mul_vec_inplace(out, rhs),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'a, F: VectorizedField> Expr<'a, F> {
+    fn binary(op: Op, lhs: Self, rhs: Self) -> Self {
+        let len = match (lhs.len, rhs.len) {
+            (Some(l), Some(r)) => {
+                assert_eq!(l, r, "expression operands differ in length");
+                Some(l)
+            }
+            (l, r) => l.or(r),
+        };
+        Self {
+            node: Node::Binary(op, Box::new(lhs.node), Box::new(rhs.node)),
+            len,
+        }
+    }
+
+    fn expect_len(&self) -> usize {
+        self.len
+            .expect("expression has no vector operands; use eval_into with an explicit output")
+    }
+
+    fn scratch(&self) -> Vec<Vec<F>> {
+        let block = core::cmp::min(self.len.unwrap_or(EXPR_BLOCK_SIZE), EXPR_BLOCK_SIZE);
+        (0..self.node.depth()).map(|_| zeroed_vec(block)).collect()
+    }
+
+    /// Evaluates the expression into `z`.
+    pub fn eval_into(&self, z: &mut [F]) {
+        if let Some(len) = self.len {
+            assert_eq!(z.len(), len);
+        }
+
+        self.eval_range(0, z, &mut self.scratch());
+    }
+
+    /// Evaluates the expression into `z`, a run of blocks per rayon task.
+    pub fn eval_into_par(&self, z: &mut [F]) {
+        if let Some(len) = self.len {
+            assert_eq!(z.len(), len);
+        }
+
+        schedule::install(VecOp::Mul, || {
+            let task_size = self.task_size(z.len());
+            z.par_chunks_mut(task_size).enumerate().for_each_init(
+                || self.scratch(),
+                |scratch, (i, z_task)| self.eval_range(i * task_size, z_task, scratch),
+            );
+        });
+    }
+
+    /// Writes elements `start..start + z.len()` into `z`, block by block.
+    fn eval_range(&self, start: usize, z: &mut [F], scratch: &mut [Vec<F>]) {
+        for (i, z_block) in z.chunks_mut(EXPR_BLOCK_SIZE).enumerate() {
+            self.node
+                .eval_block(start + i * EXPR_BLOCK_SIZE, z_block, scratch);
+        }
+    }
+
+    /// Elements per rayon task over `len` elements: the `schedule` chunk size for the operators
+    /// of the tree, which follows `ParContext` and calibration overrides, rounded up to whole
+    /// blocks.
+    fn task_size(&self, len: usize) -> usize {
+        schedule::chunk_size_scaled(VecOp::Mul, len, self.node.num_ops())
+            .next_multiple_of(EXPR_BLOCK_SIZE)
+    }
+
+    /// Reduces each block with `reduce(block_values, start)` and sums the per-block results.
+    fn reduce<R>(&self, par: bool, reduce: R) -> F
+    where
+        R: Fn(&[F], usize) -> F + Sync,
+    {
+        let len = self.expect_len();
+        let num_blocks = len.div_ceil(EXPR_BLOCK_SIZE);
+
+        let eval_reduce = |scratch: &mut (Vec<Vec<F>>, Vec<F>), i: usize| {
+            let (scratch, buf) = scratch;
+            let start = i * EXPR_BLOCK_SIZE;
+            let block = &mut buf[..core::cmp::min(EXPR_BLOCK_SIZE, len - start)];
+            self.node.eval_block(start, block, scratch);
+            reduce(block, start)
+        };
+        let init = || {
+            (
+                self.scratch(),
+                zeroed_vec(core::cmp::min(len, EXPR_BLOCK_SIZE)),
+            )
+        };
+
+        let reduce_blocks = |state: &mut (Vec<Vec<F>>, Vec<F>), blocks: Range<usize>| {
+            let partials: Vec<F> = blocks.map(|i| eval_reduce(state, i)).collect();
+            F::sum_vec(&partials)
+        };
+
+        if !par {
+            return reduce_blocks(&mut init(), 0..num_blocks);
+        }
+
+        schedule::install(VecOp::Mul, || {
+            let blocks_per_task = self.task_size(len) / EXPR_BLOCK_SIZE;
+            let partials: Vec<F> = (0..num_blocks.div_ceil(blocks_per_task))
+                .into_par_iter()
+                .map_init(init, |state, t| {
+                    let start = t * blocks_per_task;
+                    reduce_blocks(
+                        state,
+                        start..core::cmp::min(start + blocks_per_task, num_blocks),
+                    )
+                })
+                .collect();
+            F::sum_vec(&partials)
+        })
+    }
+
+    /// `Σ_i expr[i]`.
+    pub fn sum(&self) -> F {
+        self.reduce(false, |block, _| F::sum_vec(block))
+    }
+
+    /// `Σ_i expr[i]`, a run of blocks per rayon task.
+    pub fn sum_par(&self) -> F {
+        self.reduce(true, |block, _| F::sum_vec(block))
+    }
+
+    /// `Σ_i expr[i] · y[i]`.
+    pub fn dot(&self, y: &[F]) -> F {
+        assert_eq!(y.len(), self.expect_len());
+        self.reduce(false, |block, start| {
+            F::inner_product(block, &y[start..start + block.len()])
+        })
+    }
+
+    /// `Σ_i expr[i] · y[i]`, a run of blocks per rayon task.
+    pub fn dot_par(&self, y: &[F]) -> F {
+        assert_eq!(y.len(), self.expect_len());
+        self.reduce(true, |block, start| {
+            F::inner_product(block, &y[start..start + block.len()])
+        })
+    }
+}
+
+macro_rules! impl_expr_op {
+    ($trait:ident, $method:ident, $op:expr) => {
+        impl<'a, F: VectorizedField> $trait for Expr<'a, F> {
+            type Output = Expr<'a, F>;
+
+            fn $method(self, rhs: Expr<'a, F>) -> Expr<'a, F> {
+                Expr::binary($op, self, rhs)
+            }
+        }
+    };
+}
+
+impl_expr_op!(Add, add, Op::Add);
+impl_expr_op!(Sub, sub, Op::Sub);
+impl_expr_op!(Mul, mul, Op::Mul);
+
+#[cfg(all(test, feature = "ark"))]
+mod tests {
+    use super::*;
+    use crate::utils::rand_vec;
+    use ark_bn254::Fr;
+    use ark_std::Zero;
+
+    #[test]
+    fn parity_ark_expr() {
+        const NUM_OPS: usize = 3 * EXPR_BLOCK_SIZE + 17;
+        let eq: Vec<Fr> = rand_vec(NUM_OPS);
+        let a: Vec<Fr> = rand_vec(NUM_OPS);
+        let b: Vec<Fr> = rand_vec(NUM_OPS);
+        let c: Vec<Fr> = rand_vec(NUM_OPS);
+        let s: Fr = rand_vec(1)[0];
+
+        let ark_z: Vec<Fr> = (0..NUM_OPS)
+            .map(|i| eq[i] * (a[i] * b[i] - c[i]) + s)
+            .collect();
+        let e = var(&eq) * (var(&a) * var(&b) - var(&c)) + constant(s);
+
+        let mut z = vec![Fr::zero(); NUM_OPS];
+        e.eval_into(&mut z);
+        assert_eq!(z, ark_z);
+
+        let mut z_par = vec![Fr::zero(); NUM_OPS];
+        e.eval_into_par(&mut z_par);
+        assert_eq!(z_par, ark_z);
+
+        let ark_sum: Fr = ark_z.iter().sum();
+        assert_eq!(e.sum(), ark_sum);
+        assert_eq!(e.sum_par(), ark_sum);
+
+        let ark_dot: Fr = ark_z.iter().zip(&a).map(|(zi, ai)| *zi * ai).sum();
+        assert_eq!(e.dot(&a), ark_dot);
+        assert_eq!(e.dot_par(&a), ark_dot);
+    }
+
+    #[test]
+    fn parity_ark_expr_task_sizes() {
+        let _guard = schedule::OVERRIDES_LOCK.lock().unwrap();
+        const NUM_OPS: usize = 5 * EXPR_BLOCK_SIZE + 3;
+        let a: Vec<Fr> = rand_vec(NUM_OPS);
+        let b: Vec<Fr> = rand_vec(NUM_OPS);
+
+        let ark_z: Vec<Fr> = (0..NUM_OPS).map(|i| a[i] * b[i] + a[i]).collect();
+        let ark_sum: Fr = ark_z.iter().sum();
+        let e = var(&a) * var(&b) + var(&a);
+
+        // Less than a block, between whole blocks, and several blocks per task.
+        for chunk_size in [100, 1_000, 2 * EXPR_BLOCK_SIZE] {
+            schedule::set_chunk_size(VecOp::Mul, Some(chunk_size));
+            assert_eq!(
+                e.task_size(NUM_OPS),
+                chunk_size.next_multiple_of(EXPR_BLOCK_SIZE)
+            );
+
+            let mut z = vec![Fr::zero(); NUM_OPS];
+            e.eval_into_par(&mut z);
+            assert_eq!(z, ark_z);
+            assert_eq!(e.sum_par(), ark_sum);
+        }
+        schedule::set_chunk_size(VecOp::Mul, None);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::field::VectorizedField;
+
+/// Vector operations on slices of any supported field, so `Vec<F>` / `&[F]` call sites can switch
+/// to the AVX-512 kernels by importing this trait.
+pub trait VecFieldExt<F: VectorizedField> {
+    /// `z = self + y`
+    fn add_into(&self, y: &[F], z: &mut [F]);
+    fn par_add_into(&self, y: &[F], z: &mut [F]);
+    /// `self += y`
+    fn add_assign_vec(&mut self, y: &[F]);
+    fn par_add_assign_vec(&mut self, y: &[F]);
+
+    /// `z = self - y`
+    fn sub_into(&self, y: &[F], z: &mut [F]);
+    fn par_sub_into(&self, y: &[F], z: &mut [F]);
+    /// `self -= y`
+    fn sub_assign_vec(&mut self, y: &[F]);
+    fn par_sub_assign_vec(&mut self, y: &[F]);
+
+    /// `z = self * y`, elementwise.
+    fn mul_into(&self, y: &[F], z: &mut [F]);
+    fn par_mul_into(&self, y: &[F], z: &mut [F]);
+    /// `self *= y`, elementwise.
+    fn mul_assign_vec(&mut self, y: &[F]);
+    fn par_mul_assign_vec(&mut self, y: &[F]);
+
+    fn sum_simd(&self) -> F;
+    fn par_sum_simd(&self) -> F;
+
+    fn dot(&self, y: &[F]) -> F;
+    fn par_dot(&self, y: &[F]) -> F;
+}
+
+impl<F: VectorizedField> VecFieldExt<F> for [F] {
+    fn add_into(&self, y: &[F], z: &mut [F]) {
+        F::add_vec(self, y, z)
+    }
+
+    fn par_add_into(&self, y: &[F], z: &mut [F]) {
+        F::add_vec_par(self, y, z)
+    }
+
+    fn add_assign_vec(&mut self, y: &[F]) {
+        F::add_vec_inplace(self, y)
+    }
+
+    fn par_add_assign_vec(&mut self, y: &[F]) {
+        F::add_vec_inplace_par(self, y)
+    }
+
+    fn sub_into(&self, y: &[F], z: &mut [F]) {
+        F::sub_vec(self, y, z)
+    }
+
+    fn par_sub_into(&self, y: &[F], z: &mut [F]) {
+        F::sub_vec_par(self, y, z)
+    }
+
+    fn sub_assign_vec(&mut self, y: &[F]) {
+        F::sub_vec_inplace(self, y)
+    }
+
+    fn par_sub_assign_vec(&mut self, y: &[F]) {
+        F::sub_vec_inplace_par(self, y)
+    }
+
+    fn mul_into(&self, y: &[F], z: &mut [F]) {
+        F::mul_vec(self, y, z)
+    }
+
+    fn par_mul_into(&self, y: &[F], z: &mut [F]) {
+        F::mul_vec_par(self, y, z)
+    }
+
+    fn mul_assign_vec(&mut self, y: &[F]) {
+        F::mul_vec_inplace(self, y)
+    }
+
+    fn par_mul_assign_vec(&mut self, y: &[F]) {
+        F::mul_vec_inplace_par(self, y)
+    }
+
+    fn sum_simd(&self) -> F {
+        F::sum_vec(self)
+    }
+
+    fn par_sum_simd(&self) -> F {
+        F::sum_vec_par(self)
+    }
+
+    fn dot(&self, y: &[F]) -> F {
+        F::inner_product(self, y)
+    }
+
+    fn par_dot(&self, y: &[F]) -> F {
+        F::inner_product_par(self, y)
+    }
+}
+
+#[cfg(all(test, feature = "ark"))]
+mod tests {
+    use super::*;
+    use crate::ark::*;
+    use crate::utils::rand_vec;
+    use ark_bn254::Fr;
+    use ark_std::Zero;
+
+    #[test]
+    fn parity_ark_vec_field_ext() {
+        const NUM_OPS: usize = 1_000;
+        let x: Vec<Fr> = rand_vec(NUM_OPS);
+        let y: Vec<Fr> = rand_vec(NUM_OPS);
+        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
+        let mut ark_z = z.clone();
+
+        ark_batch_add(&x, &y, &mut ark_z);
+        x.add_into(&y, &mut z);
+        assert_eq!(z, ark_z);
+        x.par_add_into(&y, &mut z);
+        assert_eq!(z, ark_z);
+
+        ark_batch_sub(&x, &y, &mut ark_z);
+        x.sub_into(&y, &mut z);
+        assert_eq!(z, ark_z);
+
+        ark_batch_mul(&x, &y, &mut ark_z);
+        let mut x_mut = x.clone();
+        x_mut.mul_assign_vec(&y);
+        assert_eq!(x_mut, ark_z);
+
+        assert_eq!(x.sum_simd(), ark_batch_sum(&x));
+        assert_eq!(x.par_sum_simd(), ark_batch_sum(&x));
+        assert_eq!(x.dot(&y), ark_inner_product(&x, &y));
+        assert_eq!(x.par_dot(&y), ark_inner_product(&x, &y));
+    }
+}
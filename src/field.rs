@@ -0,0 +1,191 @@
+use alloc::{vec, vec::Vec};
+use core::fmt::Debug;
+
+/// A field with AVX-512 vector kernels.
+///
+/// # Safety
+///
+/// Implementors must be laid out as `LIMBS` little-endian `u64` limbs in Montgomery form,
+/// with the all-zero bit pattern representing zero, so buffers can be zero-allocated and
+/// handed to the assembly kernels as raw limbs.
+pub unsafe trait VectorizedField: Copy + Send + Sync + PartialEq + Debug + 'static {
+    const LIMBS: usize;
+
+    fn add_vec(x: &[Self], y: &[Self], z: &mut [Self]);
+    fn add_vec_par(x: &[Self], y: &[Self], z: &mut [Self]);
+    fn add_vec_inplace(x: &mut [Self], y: &[Self]);
+    fn add_vec_inplace_par(x: &mut [Self], y: &[Self]);
+
+    fn sub_vec(x: &[Self], y: &[Self], z: &mut [Self]);
+    fn sub_vec_par(x: &[Self], y: &[Self], z: &mut [Self]);
+    fn sub_vec_inplace(x: &mut [Self], y: &[Self]);
+    fn sub_vec_inplace_par(x: &mut [Self], y: &[Self]);
+
+    fn mul_vec(x: &[Self], y: &[Self], z: &mut [Self]);
+    fn mul_vec_par(x: &[Self], y: &[Self], z: &mut [Self]);
+    fn mul_vec_inplace(x: &mut [Self], y: &[Self]);
+    fn mul_vec_inplace_par(x: &mut [Self], y: &[Self]);
+
+    fn sum_vec(x: &[Self]) -> Self;
+    fn sum_vec_par(x: &[Self]) -> Self;
+
+    fn inner_product(x: &[Self], y: &[Self]) -> Self;
+    fn inner_product_par(x: &[Self], y: &[Self]) -> Self;
+}
+
+/// Implements `VectorizedField` for `$field` by forwarding each method to the function listed in
+/// the same position, in the order the trait declares them.
+#[cfg(feature = "ark")]
+macro_rules! impl_vectorized_field {
+    (
+        $field:ty, $limbs:expr;
+        [
+            $add_vec:path, $add_vec_par:path, $add_vec_inplace:path, $add_vec_inplace_par:path,
+            $sub_vec:path, $sub_vec_par:path, $sub_vec_inplace:path, $sub_vec_inplace_par:path,
+            $mul_vec:path, $mul_vec_par:path, $mul_vec_inplace:path, $mul_vec_inplace_par:path,
+            $sum_vec:path, $sum_vec_par:path, $inner_product:path, $inner_product_par:path $(,)?
+        ]
+    ) => {
+        unsafe impl VectorizedField for $field {
+            const LIMBS: usize = $limbs;
+
+            fn add_vec(x: &[Self], y: &[Self], z: &mut [Self]) {
+                $add_vec(x, y, z)
+            }
+
+            fn add_vec_par(x: &[Self], y: &[Self], z: &mut [Self]) {
+                $add_vec_par(x, y, z)
+            }
+
+            fn add_vec_inplace(x: &mut [Self], y: &[Self]) {
+                $add_vec_inplace(x, y)
+            }
+
+            fn add_vec_inplace_par(x: &mut [Self], y: &[Self]) {
+                $add_vec_inplace_par(x, y)
+            }
+
+            fn sub_vec(x: &[Self], y: &[Self], z: &mut [Self]) {
+                $sub_vec(x, y, z)
+            }
+
+            fn sub_vec_par(x: &[Self], y: &[Self], z: &mut [Self]) {
+                $sub_vec_par(x, y, z)
+            }
+
+            fn sub_vec_inplace(x: &mut [Self], y: &[Self]) {
+                $sub_vec_inplace(x, y)
+            }
+
+            fn sub_vec_inplace_par(x: &mut [Self], y: &[Self]) {
+                $sub_vec_inplace_par(x, y)
+            }
+
+            fn mul_vec(x: &[Self], y: &[Self], z: &mut [Self]) {
+                $mul_vec(x, y, z)
+            }
+
+            fn mul_vec_par(x: &[Self], y: &[Self], z: &mut [Self]) {
+                $mul_vec_par(x, y, z)
+            }
+
+            fn mul_vec_inplace(x: &mut [Self], y: &[Self]) {
+                $mul_vec_inplace(x, y)
+            }
+
+            fn mul_vec_inplace_par(x: &mut [Self], y: &[Self]) {
+                $mul_vec_inplace_par(x, y)
+            }
+
+            fn sum_vec(x: &[Self]) -> Self {
+                $sum_vec(x)
+            }
+
+            fn sum_vec_par(x: &[Self]) -> Self {
+                $sum_vec_par(x)
+            }
+
+            fn inner_product(x: &[Self], y: &[Self]) -> Self {
+                $inner_product(x, y)
+            }
+
+            fn inner_product_par(x: &[Self], y: &[Self]) -> Self {
+                $inner_product_par(x, y)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "ark")]
+impl_vectorized_field! {
+    ark_bn254::Fr, 4;
+    [
+        crate::add_vec_bn254,
+        crate::add_vec_par_bn254,
+        crate::add_vec_inplace_bn254,
+        crate::add_vec_inplace_par_bn254,
+        crate::sub_vec_bn254,
+        crate::sub_vec_par_bn254,
+        crate::sub_vec_inplace_bn254,
+        crate::sub_vec_inplace_par_bn254,
+        crate::mul_vec_bn254,
+        crate::mul_vec_par_bn254,
+        crate::mul_vec_inplace_bn254,
+        crate::mul_vec_inplace_par_bn254,
+        crate::sum_vec_bn254,
+        crate::sum_vec_par_bn254,
+        crate::inner_product_bn254,
+        crate::inner_product_par_bn254,
+    ]
+}
+
+#[cfg(feature = "ark")]
+impl_vectorized_field! {
+    ark_bn254::Fq, 4;
+    [
+        crate::add_vec_bn254_fq,
+        crate::add_vec_par_bn254_fq,
+        crate::add_vec_inplace_bn254_fq,
+        crate::add_vec_inplace_par_bn254_fq,
+        crate::sub_vec_bn254_fq,
+        crate::sub_vec_par_bn254_fq,
+        crate::sub_vec_inplace_bn254_fq,
+        crate::sub_vec_inplace_par_bn254_fq,
+        crate::mul_vec_bn254_fq,
+        crate::mul_vec_par_bn254_fq,
+        crate::mul_vec_inplace_bn254_fq,
+        crate::mul_vec_inplace_par_bn254_fq,
+        crate::sum_vec_bn254_fq,
+        crate::sum_vec_par_bn254_fq,
+        crate::inner_product_bn254_fq,
+        crate::inner_product_par_bn254_fq,
+    ]
+}
+
+#[cfg(feature = "ark")]
+impl_vectorized_field! {
+    ark_bls12_381::Fq, 6;
+    [
+        crate::add_vec_bls12_381_fq,
+        crate::add_vec_par_bls12_381_fq,
+        crate::add_vec_inplace_bls12_381_fq,
+        crate::add_vec_inplace_par_bls12_381_fq,
+        crate::sub_vec_bls12_381_fq,
+        crate::sub_vec_par_bls12_381_fq,
+        crate::sub_vec_inplace_bls12_381_fq,
+        crate::sub_vec_inplace_par_bls12_381_fq,
+        crate::mul_vec_bls12_381_fq,
+        crate::mul_vec_par_bls12_381_fq,
+        crate::mul_vec_inplace_bls12_381_fq,
+        crate::mul_vec_inplace_par_bls12_381_fq,
+        crate::sum_vec_bls12_381_fq,
+        crate::sum_vec_par_bls12_381_fq,
+        crate::inner_product_bls12_381_fq,
+        crate::inner_product_par_bls12_381_fq,
+    ]
+}
+
+/// Vector of `len` zeros, relying on the all-zero representation guaranteed by `VectorizedField`.
+pub(crate) fn zeroed_vec<F: VectorizedField>(len: usize) -> Vec<F> {
+    vec![unsafe { core::mem::zeroed() }; len]
+}
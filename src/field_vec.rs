@@ -0,0 +1,531 @@
+use alloc::{
+    alloc::{alloc_zeroed, dealloc, handle_alloc_error, Layout},
+    vec,
+    vec::Vec,
+};
+use core::{
+    fmt,
+    iter::Sum,
+    marker::PhantomData,
+    mem,
+    ops::{Add, AddAssign, Deref, DerefMut, Mul, MulAssign, Sub, SubAssign},
+    ptr::NonNull,
+    slice,
+};
+
+use crate::{
+    field::VectorizedField,
+    par::*,
+    schedule::{self, VecOp},
+};
+
+/// Alignment of `FieldVec` storage, one ZMM register.
+pub const FIELD_VEC_ALIGN: usize = 64;
+
+/// Length of the scalar broadcast buffer used by the scalar operators.
+const BROADCAST_LEN: usize = 1024;
+
+/// Huge page size assumed when rounding huge-page mappings, 2 MiB on x86-64.
+#[cfg(target_os = "linux")]
+const HUGE_PAGE_SIZE: usize = 2 << 20;
+
+/// Where the storage of a `FieldVec` comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Backing {
+    /// The global allocator.
+    #[default]
+    Heap,
+    /// An anonymous mapping backed by `MAP_HUGETLB` pages, or failing that, by transparent huge
+    /// pages requested with `madvise(MADV_HUGEPAGE)`.
+    HugePages,
+}
+
+/// Owned vector of field elements whose arithmetic runs on the assembly kernels. The storage is
+/// 64-byte aligned, so the mul kernels run their AVX-512 loop on whole cache lines.
+pub struct FieldVec<F: VectorizedField> {
+    ptr: NonNull<F>,
+    len: usize,
+    backing: Backing,
+    _marker: PhantomData<F>,
+}
+
+unsafe impl<F: VectorizedField> Send for FieldVec<F> {}
+unsafe impl<F: VectorizedField> Sync for FieldVec<F> {}
+
+impl<F: VectorizedField> FieldVec<F> {
+    /// Layout of `len` elements, panicking like `Vec` if its size does not fit an `isize`.
+    fn layout(len: usize) -> Layout {
+        Layout::array::<F>(len)
+            .and_then(|layout| layout.align_to(FIELD_VEC_ALIGN))
+            .expect("capacity overflow")
+    }
+
+    /// Vector of `len` zeros.
+    pub fn zeros(len: usize) -> Self {
+        Self::zeros_in(len, Backing::Heap)
+    }
+
+    /// Vector of `len` zeros backed by huge pages, which avoids most TLB misses on tables of
+    /// hundreds of MiB. Falls back to the heap where huge pages are unavailable.
+    pub fn zeros_huge(len: usize) -> Self {
+        Self::zeros_in(len, Backing::HugePages)
+    }
+
+    /// Vector of `len` zeros allocated from `backing`, falling back to the heap if that fails.
+    pub fn zeros_in(len: usize, backing: Backing) -> Self {
+        if len == 0 || mem::size_of::<F>() == 0 {
+            return Self {
+                ptr: NonNull::dangling(),
+                len,
+                backing: Backing::Heap,
+                _marker: PhantomData,
+            };
+        }
+
+        let layout = Self::layout(len);
+        if backing == Backing::HugePages {
+            if let Some(ptr) = map_huge(layout.size()) {
+                return Self {
+                    ptr: ptr.cast(),
+                    len,
+                    backing,
+                    _marker: PhantomData,
+                };
+            }
+        }
+
+        // Safety: the layout is non-zero sized, and `VectorizedField` guarantees that all-zero
+        // bytes are a valid (zero) element.
+        let ptr = unsafe { alloc_zeroed(layout) } as *mut F;
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| handle_alloc_error(layout));
+
+        Self {
+            ptr,
+            len,
+            backing: Backing::Heap,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn from_slice(x: &[F]) -> Self {
+        Self::from_slice_in(x, Backing::Heap)
+    }
+
+    pub fn from_slice_in(x: &[F], backing: Backing) -> Self {
+        let mut v = Self::zeros_in(x.len(), backing);
+        v.copy_from_slice(x);
+        v
+    }
+
+    /// Where the storage actually lives, `Heap` if a huge-page allocation fell back.
+    pub fn backing(&self) -> Backing {
+        self.backing
+    }
+
+    pub fn as_slice(&self) -> &[F] {
+        self
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [F] {
+        self
+    }
+
+    pub fn to_vec(&self) -> Vec<F> {
+        self.as_slice().to_vec()
+    }
+
+    /// Sum of all elements.
+    pub fn sum(&self) -> F {
+        if !schedule::should_parallelize(VecOp::Sum, self.len) {
+            F::sum_vec(self)
+        } else {
+            F::sum_vec_par(self)
+        }
+    }
+
+    /// Inner product with `other`.
+    pub fn dot(&self, other: &[F]) -> F {
+        assert_eq!(self.len, other.len());
+        if !schedule::should_parallelize(VecOp::InnerProduct, self.len) {
+            F::inner_product(self, other)
+        } else {
+            F::inner_product_par(self, other)
+        }
+    }
+
+    fn add_assign_slice(&mut self, other: &[F]) {
+        assert_eq!(self.len, other.len());
+        if !schedule::should_parallelize(VecOp::Add, self.len) {
+            F::add_vec_inplace(self, other);
+        } else {
+            F::add_vec_inplace_par(self, other);
+        }
+    }
+
+    fn sub_assign_slice(&mut self, other: &[F]) {
+        assert_eq!(self.len, other.len());
+        if !schedule::should_parallelize(VecOp::Sub, self.len) {
+            F::sub_vec_inplace(self, other);
+        } else {
+            F::sub_vec_inplace_par(self, other);
+        }
+    }
+
+    fn mul_assign_slice(&mut self, other: &[F]) {
+        assert_eq!(self.len, other.len());
+        if !schedule::should_parallelize(VecOp::Mul, self.len) {
+            F::mul_vec_inplace(self, other);
+        } else {
+            F::mul_vec_inplace_par(self, other);
+        }
+    }
+
+    /// Applies `op(chunk, [scalar; chunk.len()])` across the vector without materializing a
+    /// full-length broadcast of `scalar`.
+    fn scalar_op(&mut self, scalar: F, op: fn(&mut [F], &[F]), vec_op: VecOp) {
+        let broadcast = vec![scalar; core::cmp::min(self.len, BROADCAST_LEN)];
+        let apply = |chunk: &mut [F]| {
+            for block in chunk.chunks_mut(BROADCAST_LEN) {
+                op(block, &broadcast[..block.len()]);
+            }
+        };
+
+        if !schedule::should_parallelize(vec_op, self.len) {
+            apply(self);
+        } else {
+            schedule::install(vec_op, || {
+                let chunk_size = schedule::chunk_size(vec_op, self.len);
+                self.par_chunks_mut(chunk_size).for_each(apply);
+            });
+        }
+    }
+
+    fn binary_op(
+        x: &[F],
+        y: &[F],
+        serial: fn(&[F], &[F], &mut [F]),
+        par: fn(&[F], &[F], &mut [F]),
+        vec_op: VecOp,
+    ) -> Self {
+        assert_eq!(x.len(), y.len());
+        let mut z = Self::zeros(x.len());
+        if !schedule::should_parallelize(vec_op, x.len()) {
+            serial(x, y, &mut z);
+        } else {
+            par(x, y, &mut z);
+        }
+        z
+    }
+}
+
+impl<F: VectorizedField> Drop for FieldVec<F> {
+    fn drop(&mut self) {
+        if self.len == 0 || mem::size_of::<F>() == 0 {
+            return;
+        }
+        let layout = Self::layout(self.len);
+        match self.backing {
+            Backing::Heap => unsafe { dealloc(self.ptr.as_ptr() as *mut u8, layout) },
+            Backing::HugePages => unmap_huge(self.ptr.cast(), layout.size()),
+        }
+    }
+}
+
+/// Maps `size` zeroed bytes on huge pages, trying hugetlbfs pages before transparent ones.
+#[cfg(target_os = "linux")]
+fn map_huge(size: usize) -> Option<NonNull<u8>> {
+    let size = size.next_multiple_of(HUGE_PAGE_SIZE);
+    let map = |flags| {
+        // Safety: an anonymous private mapping does not alias any existing memory.
+        let ptr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | flags,
+                -1,
+                0,
+            )
+        };
+        (ptr != libc::MAP_FAILED).then_some(ptr)
+    };
+
+    let ptr = map(libc::MAP_HUGETLB).or_else(|| {
+        let ptr = map(0)?;
+        // Best effort: without THP the mapping still works on regular pages.
+        unsafe { libc::madvise(ptr, size, libc::MADV_HUGEPAGE) };
+        Some(ptr)
+    })?;
+    NonNull::new(ptr as *mut u8)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn map_huge(_size: usize) -> Option<NonNull<u8>> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn unmap_huge(ptr: NonNull<u8>, size: usize) {
+    let size = size.next_multiple_of(HUGE_PAGE_SIZE);
+    unsafe { libc::munmap(ptr.as_ptr() as *mut libc::c_void, size) };
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unmap_huge(_ptr: NonNull<u8>, _size: usize) {
+    unreachable!("huge-page mappings are only created on Linux")
+}
+
+impl<F: VectorizedField> Deref for FieldVec<F> {
+    type Target = [F];
+
+    fn deref(&self) -> &[F] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<F: VectorizedField> DerefMut for FieldVec<F> {
+    fn deref_mut(&mut self) -> &mut [F] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<F: VectorizedField> Clone for FieldVec<F> {
+    fn clone(&self) -> Self {
+        Self::from_slice_in(self, self.backing)
+    }
+}
+
+impl<F: VectorizedField> PartialEq for FieldVec<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<F: VectorizedField> fmt::Debug for FieldVec<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<F: VectorizedField> From<&[F]> for FieldVec<F> {
+    fn from(x: &[F]) -> Self {
+        Self::from_slice(x)
+    }
+}
+
+impl<F: VectorizedField> From<Vec<F>> for FieldVec<F> {
+    fn from(x: Vec<F>) -> Self {
+        Self::from_slice(&x)
+    }
+}
+
+impl<F: VectorizedField> From<FieldVec<F>> for Vec<F> {
+    fn from(x: FieldVec<F>) -> Self {
+        x.to_vec()
+    }
+}
+
+impl<F: VectorizedField> FromIterator<F> for FieldVec<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<F>>())
+    }
+}
+
+macro_rules! impl_binary_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $assign_slice:ident, $serial:ident, $par:ident) => {
+        impl<F: VectorizedField> $assign_trait<&FieldVec<F>> for FieldVec<F> {
+            fn $assign_method(&mut self, rhs: &FieldVec<F>) {
+                self.$assign_slice(rhs);
+            }
+        }
+
+        impl<F: VectorizedField> $assign_trait<FieldVec<F>> for FieldVec<F> {
+            fn $assign_method(&mut self, rhs: FieldVec<F>) {
+                self.$assign_slice(&rhs);
+            }
+        }
+
+        impl<F: VectorizedField> $trait<&FieldVec<F>> for &FieldVec<F> {
+            type Output = FieldVec<F>;
+
+            fn $method(self, rhs: &FieldVec<F>) -> FieldVec<F> {
+                FieldVec::binary_op(self, rhs, F::$serial, F::$par, VecOp::$trait)
+            }
+        }
+
+        impl<F: VectorizedField> $trait<&FieldVec<F>> for FieldVec<F> {
+            type Output = FieldVec<F>;
+
+            fn $method(mut self, rhs: &FieldVec<F>) -> FieldVec<F> {
+                self.$assign_slice(rhs);
+                self
+            }
+        }
+
+        impl<F: VectorizedField> $trait<FieldVec<F>> for FieldVec<F> {
+            type Output = FieldVec<F>;
+
+            fn $method(mut self, rhs: FieldVec<F>) -> FieldVec<F> {
+                self.$assign_slice(&rhs);
+                self
+            }
+        }
+    };
+}
+
+impl_binary_op!(
+    Add,
+    add,
+    AddAssign,
+    add_assign,
+    add_assign_slice,
+    add_vec,
+    add_vec_par
+);
+impl_binary_op!(
+    Sub,
+    sub,
+    SubAssign,
+    sub_assign,
+    sub_assign_slice,
+    sub_vec,
+    sub_vec_par
+);
+impl_binary_op!(
+    Mul,
+    mul,
+    MulAssign,
+    mul_assign,
+    mul_assign_slice,
+    mul_vec,
+    mul_vec_par
+);
+
+macro_rules! impl_scalar_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $kernel:ident) => {
+        impl<F: VectorizedField> $assign_trait<F> for FieldVec<F> {
+            fn $assign_method(&mut self, rhs: F) {
+                self.scalar_op(rhs, F::$kernel, VecOp::$trait);
+            }
+        }
+
+        impl<F: VectorizedField> $trait<F> for FieldVec<F> {
+            type Output = FieldVec<F>;
+
+            fn $method(mut self, rhs: F) -> FieldVec<F> {
+                self.scalar_op(rhs, F::$kernel, VecOp::$trait);
+                self
+            }
+        }
+
+        impl<F: VectorizedField> $trait<F> for &FieldVec<F> {
+            type Output = FieldVec<F>;
+
+            fn $method(self, rhs: F) -> FieldVec<F> {
+                self.clone().$method(rhs)
+            }
+        }
+    };
+}
+
+impl_scalar_op!(Add, add, AddAssign, add_assign, add_vec_inplace);
+impl_scalar_op!(Sub, sub, SubAssign, sub_assign, sub_vec_inplace);
+impl_scalar_op!(Mul, mul, MulAssign, mul_assign, mul_vec_inplace);
+
+/// Elementwise sum of vectors. Summing no vectors yields an empty vector.
+impl<F: VectorizedField> Sum for FieldVec<F> {
+    fn sum<I: Iterator<Item = FieldVec<F>>>(mut iter: I) -> Self {
+        let Some(mut acc) = iter.next() else {
+            return Self::zeros(0);
+        };
+        for v in iter {
+            acc += &v;
+        }
+        acc
+    }
+}
+
+impl<'a, F: VectorizedField> Sum<&'a FieldVec<F>> for FieldVec<F> {
+    fn sum<I: Iterator<Item = &'a FieldVec<F>>>(mut iter: I) -> Self {
+        let Some(first) = iter.next() else {
+            return Self::zeros(0);
+        };
+        let mut acc = first.clone();
+        for v in iter {
+            acc += v;
+        }
+        acc
+    }
+}
+
+#[cfg(all(test, feature = "ark"))]
+mod tests {
+    use super::*;
+    use crate::ark::*;
+    use crate::utils::rand_vec;
+    use ark_bn254::Fr;
+    use ark_std::Zero;
+
+    #[test]
+    fn field_vec_alignment() {
+        for len in [1, 3, 1_000] {
+            let v = FieldVec::<Fr>::zeros(len);
+            assert_eq!(v.as_ptr() as usize % FIELD_VEC_ALIGN, 0);
+            assert!(v.iter().all(Fr::is_zero));
+
+            let huge = FieldVec::<Fr>::zeros_huge(len);
+            assert_eq!(huge.as_ptr() as usize % FIELD_VEC_ALIGN, 0);
+            assert!(huge.iter().all(Fr::is_zero));
+            assert_eq!(huge.clone().backing(), huge.backing());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn field_vec_capacity_overflow() {
+        FieldVec::<Fr>::zeros(usize::MAX / 16);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn field_vec_huge_capacity_overflow() {
+        FieldVec::<Fr>::zeros_huge(usize::MAX / 16);
+    }
+
+    #[test]
+    fn parity_ark_field_vec_ops() {
+        for len in [100, (1 << 16) + 3] {
+            let x: Vec<Fr> = rand_vec(len);
+            let y: Vec<Fr> = rand_vec(len);
+            let s: Fr = rand_vec(1)[0];
+            let fx = FieldVec::from_slice(&x);
+            let fy = FieldVec::from_slice(&y);
+            let mut ark_z = vec![Fr::zero(); len];
+
+            ark_batch_add(&x, &y, &mut ark_z);
+            assert_eq!((&fx + &fy).as_slice(), ark_z);
+            assert_eq!((fx.clone() + &fy).as_slice(), ark_z);
+
+            ark_batch_sub(&x, &y, &mut ark_z);
+            assert_eq!((&fx - &fy).as_slice(), ark_z);
+
+            ark_batch_mul(&x, &y, &mut ark_z);
+            assert_eq!((&fx * &fy).as_slice(), ark_z);
+            let mut fz = fx.clone();
+            fz *= &fy;
+            assert_eq!(fz.as_slice(), ark_z);
+
+            let scaled: Vec<Fr> = x.iter().map(|xi| *xi * s).collect();
+            assert_eq!((&fx * s).as_slice(), scaled);
+            let shifted: Vec<Fr> = x.iter().map(|xi| *xi + s).collect();
+            assert_eq!((&fx + s).as_slice(), shifted);
+
+            assert_eq!(fx.dot(&fy), ark_inner_product(&x, &y));
+            assert_eq!(fx.sum(), ark_batch_sum(&x));
+
+            ark_batch_add(&x, &y, &mut ark_z);
+            let total: FieldVec<Fr> = [fx, fy].into_iter().sum();
+            assert_eq!(total.as_slice(), ark_z);
+        }
+    }
+}
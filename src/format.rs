@@ -0,0 +1,280 @@
+//! Versioned binary file format for vectors of field elements.
+//!
+//! A file is a bincode header followed by the elements as raw little-endian limbs:
+//!
+//! ```text
+//! magic      8 bytes, "VFVEC\0\0\0"
+//! version    u32, FORMAT_VERSION
+//! field      string, "bn254-fr"
+//! modulus    u64, hash of the modulus limbs
+//! repr       u8, 0 = Montgomery, 1 = canonical
+//! len        u64, number of elements
+//! checksum   u64, hash of the element limbs as stored
+//! ```
+//!
+//! Integers are fixed-width little-endian and strings carry a u64 length prefix. Both hashes are
+//! FNV-1a over 64-bit limbs. Canonical files are converted to and from Montgomery form with the
+//! mul kernel, in chunks of [`IO_CHUNK`] elements.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use ark_bn254::Fr;
+use ark_ff::BigInt;
+use bincode::Options;
+
+use crate::{
+    canonical_to_montgomery_bn254, constants, montgomery_to_canonical_bn254,
+    utils::{limb_bytes, limb_bytes_mut, limbs},
+    validate::find_non_canonical,
+};
+
+pub const FORMAT_VERSION: u32 = 1;
+
+const MAGIC: [u8; 8] = *b"VFVEC\0\0\0";
+
+const BN254_FR_ID: &str = "bn254-fr";
+
+/// Upper bound on the encoded header, so a corrupt length prefix cannot trigger a huge allocation.
+const HEADER_LIMIT: u64 = 256;
+
+/// Elements converted, hashed and copied per step.
+const IO_CHUNK: usize = 1 << 16;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// How elements are stored in a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Representation {
+    /// The in-memory arkworks representation, `x * R mod r`. Loads without conversion.
+    Montgomery,
+    /// The residue itself, `x mod r`, as most other tools expect.
+    Canonical,
+}
+
+impl Representation {
+    fn code(self) -> u8 {
+        match self {
+            Representation::Montgomery => 0,
+            Representation::Canonical => 1,
+        }
+    }
+
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(Representation::Montgomery),
+            1 => Some(Representation::Canonical),
+            _ => None,
+        }
+    }
+}
+
+/// magic, version, field, modulus hash, representation, length, checksum
+type Header = ([u8; 8], u32, String, u64, u8, u64, u64);
+
+fn header_options() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_limit(HEADER_LIMIT)
+}
+
+fn fnv1a(hash: u64, words: &[u64]) -> u64 {
+    words
+        .iter()
+        .fold(hash, |hash, &w| (hash ^ w).wrapping_mul(FNV_PRIME))
+}
+
+fn modulus_hash() -> u64 {
+    fnv1a(FNV_OFFSET, &constants::BN254_FR_MODULUS)
+}
+
+fn invalid(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+/// Calls `f` on the limbs of `x` as they are stored in `repr`, one chunk at a time.
+fn for_each_stored_chunk(
+    x: &[Fr],
+    repr: Representation,
+    mut f: impl FnMut(&[[u64; 4]]) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut buf = vec![[0u64; 4]; std::cmp::min(x.len(), IO_CHUNK)];
+    for chunk in x.chunks(IO_CHUNK) {
+        match repr {
+            Representation::Montgomery => f(limbs(chunk))?,
+            Representation::Canonical => {
+                let buf = &mut buf[..chunk.len()];
+                montgomery_to_canonical_bn254(chunk, buf);
+                f(buf)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes `x` to `writer` in the format above, storing the elements in `repr`.
+pub fn write_vec_bn254<W: Write>(mut writer: W, x: &[Fr], repr: Representation) -> io::Result<()> {
+    // The checksum precedes the data, so canonical output is converted twice rather than
+    // buffered in full.
+    let mut checksum = FNV_OFFSET;
+    for_each_stored_chunk(x, repr, |chunk| {
+        checksum = fnv1a(checksum, chunk.as_flattened());
+        Ok(())
+    })?;
+
+    let header: Header = (
+        MAGIC,
+        FORMAT_VERSION,
+        BN254_FR_ID.to_string(),
+        modulus_hash(),
+        repr.code(),
+        x.len() as u64,
+        checksum,
+    );
+    header_options()
+        .serialize_into(&mut writer, &header)
+        .map_err(|e| match *e {
+            bincode::ErrorKind::Io(e) => e,
+            e => invalid(e.to_string()),
+        })?;
+
+    for_each_stored_chunk(x, repr, |chunk| writer.write_all(limb_bytes(chunk)))?;
+    writer.flush()
+}
+
+/// Reads a vector written by [`write_vec_bn254`], in either representation.
+///
+/// Fails with `InvalidData` if the file is for another field or modulus, holds an element that
+/// is not below the modulus, or does not match its checksum.
+pub fn read_vec_bn254<R: Read>(mut reader: R) -> io::Result<Vec<Fr>> {
+    let (magic, version, field, modulus, repr, len, checksum): Header = header_options()
+        .deserialize_from(&mut reader)
+        .map_err(|e| match *e {
+            bincode::ErrorKind::Io(e) => e,
+            _ => invalid("not a vectorized-fields vector file"),
+        })?;
+
+    if magic != MAGIC {
+        return Err(invalid("not a vectorized-fields vector file"));
+    }
+    if version > FORMAT_VERSION {
+        return Err(invalid(format!(
+            "format version {version} is newer than the supported {FORMAT_VERSION}"
+        )));
+    }
+    if field != BN254_FR_ID {
+        return Err(invalid(format!(
+            "file holds {field:?} elements, expected {BN254_FR_ID:?}"
+        )));
+    }
+    if modulus != modulus_hash() {
+        return Err(invalid("file modulus does not match bn254-fr"));
+    }
+    let repr = Representation::from_code(repr)
+        .ok_or_else(|| invalid(format!("unknown representation {repr}")))?;
+    let len = usize::try_from(len).map_err(|_| invalid("length overflows usize"))?;
+
+    // Grow chunk by chunk so that a truncated file fails before a bogus length is allocated.
+    let mut out: Vec<Fr> = Vec::new();
+    let mut buf = vec![[0u64; 4]; std::cmp::min(len, IO_CHUNK)];
+    let mut hash = FNV_OFFSET;
+    while out.len() < len {
+        let start = out.len();
+        let buf = &mut buf[..std::cmp::min(IO_CHUNK, len - start)];
+        reader.read_exact(limb_bytes_mut(buf))?;
+        hash = fnv1a(hash, buf.as_flattened());
+
+        if let Some(i) = find_non_canonical(buf, &constants::BN254_FR_MODULUS) {
+            return Err(invalid(format!(
+                "element {} is not below the modulus",
+                start + i
+            )));
+        }
+        match repr {
+            Representation::Montgomery => {
+                out.extend(buf.iter().map(|&x| Fr::new_unchecked(BigInt(x))));
+            }
+            Representation::Canonical => {
+                out.resize(start + buf.len(), Fr::new_unchecked(BigInt([0; 4])));
+                canonical_to_montgomery_bn254(buf, &mut out[start..]);
+            }
+        }
+    }
+
+    if hash != checksum {
+        return Err(invalid("checksum mismatch"));
+    }
+    Ok(out)
+}
+
+/// Saves `x` to the file at `path`, replacing it.
+pub fn save_vec_bn254(path: impl AsRef<Path>, x: &[Fr], repr: Representation) -> io::Result<()> {
+    write_vec_bn254(BufWriter::new(File::create(path)?), x, repr)
+}
+
+pub fn load_vec_bn254(path: impl AsRef<Path>) -> io::Result<Vec<Fr>> {
+    read_vec_bn254(BufReader::new(File::open(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::rand_vec;
+
+    #[test]
+    fn vec_file_roundtrip() {
+        for len in [0, 5, IO_CHUNK + 3] {
+            let x: Vec<Fr> = rand_vec(len);
+            for repr in [Representation::Montgomery, Representation::Canonical] {
+                let mut bytes = Vec::new();
+                write_vec_bn254(&mut bytes, &x, repr).unwrap();
+                assert_eq!(read_vec_bn254(bytes.as_slice()).unwrap(), x);
+            }
+        }
+
+        let x: Vec<Fr> = rand_vec(100);
+        let path =
+            std::env::temp_dir().join(format!("vectorized-fields-vec-{}.bin", std::process::id()));
+        save_vec_bn254(&path, &x, Representation::Canonical).unwrap();
+        let loaded = load_vec_bn254(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, x);
+    }
+
+    #[test]
+    fn vec_file_rejects_mismatches() {
+        let x: Vec<Fr> = rand_vec(10);
+        let mut bytes = Vec::new();
+        write_vec_bn254(&mut bytes, &x, Representation::Canonical).unwrap();
+        let read = |bytes: &[u8]| read_vec_bn254(bytes).unwrap_err().kind();
+
+        // magic (8) + version (4) + field length (8) + "bn254-fr" (8), then the modulus hash.
+        let field_at = 20;
+        let modulus_at = field_at + BN254_FR_ID.len();
+
+        let mut other_field = bytes.clone();
+        other_field[field_at..modulus_at].copy_from_slice(b"bn254-fq");
+        assert_eq!(read(&other_field), io::ErrorKind::InvalidData);
+
+        let mut other_modulus = bytes.clone();
+        other_modulus[modulus_at] ^= 1;
+        assert_eq!(read(&other_modulus), io::ErrorKind::InvalidData);
+
+        let mut corrupt = bytes.clone();
+        corrupt[bytes.len() - 10 * 32] ^= 1;
+        assert_eq!(read(&corrupt), io::ErrorKind::InvalidData);
+
+        assert_eq!(
+            read(&bytes[..bytes.len() - 1]),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(
+            read(b"this is certainly not a vectorized-fields vector file"),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
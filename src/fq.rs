@@ -0,0 +1,21 @@
+//! The vector kernels over the BN254 base field `Fq`.
+//!
+//! The assembly takes the modulus as a parameter, so these are the [`limbs`] operations run
+//! against [`Modulus::BN254_FQ`].
+
+use ark_bn254::Fq;
+
+use crate::{
+    limbs::{self, Modulus},
+    utils::field_functions,
+};
+
+field_functions! {
+    Fq, limbs, &Modulus::BN254_FQ, parity_ark_fq;
+    [
+        add_vec_bn254_fq, add_vec_par_bn254_fq, add_vec_inplace_bn254_fq, add_vec_inplace_par_bn254_fq,
+        sub_vec_bn254_fq, sub_vec_par_bn254_fq, sub_vec_inplace_bn254_fq, sub_vec_inplace_par_bn254_fq,
+        mul_vec_bn254_fq, mul_vec_par_bn254_fq, mul_vec_inplace_bn254_fq, mul_vec_inplace_par_bn254_fq,
+        sum_vec_bn254_fq, sum_vec_par_bn254_fq, inner_product_bn254_fq, inner_product_par_bn254_fq,
+    ]
+}
@@ -0,0 +1,170 @@
+//! `VectorizedField` for `halo2curves::bn256::{Fr, Fq}`.
+//!
+//! The halo2curves types hold the same four little-endian Montgomery limbs as their arkworks
+//! counterparts, with the same `R = 2^256`, so slices are reinterpreted and handed to the
+//! arkworks kernels unchanged. The layout is asserted at compile time and the representation
+//! checked by the tests below.
+
+use std::mem::{align_of, size_of};
+
+use crate::field::VectorizedField;
+
+/// Reinterprets a slice of `A` as a slice of `B`, for types asserted to share a layout.
+fn cast<A, B>(x: &[A]) -> &[B] {
+    assert!(size_of::<A>() == size_of::<B>() && align_of::<A>() == align_of::<B>());
+    unsafe { std::slice::from_raw_parts(x.as_ptr() as *const B, x.len()) }
+}
+
+fn cast_mut<A, B>(x: &mut [A]) -> &mut [B] {
+    assert!(size_of::<A>() == size_of::<B>() && align_of::<A>() == align_of::<B>());
+    unsafe { std::slice::from_raw_parts_mut(x.as_mut_ptr() as *mut B, x.len()) }
+}
+
+fn cast_one<A: Copy, B: Copy>(x: A) -> B {
+    cast::<A, B>(&[x])[0]
+}
+
+macro_rules! impl_via_ark {
+    ($halo2:ty, $ark:ty) => {
+        const _: () = assert!(
+            size_of::<$halo2>() == size_of::<$ark>() && align_of::<$halo2>() == align_of::<$ark>()
+        );
+
+        unsafe impl VectorizedField for $halo2 {
+            const LIMBS: usize = <$ark as VectorizedField>::LIMBS;
+
+            fn add_vec(x: &[Self], y: &[Self], z: &mut [Self]) {
+                <$ark>::add_vec(cast(x), cast(y), cast_mut(z))
+            }
+
+            fn add_vec_par(x: &[Self], y: &[Self], z: &mut [Self]) {
+                <$ark>::add_vec_par(cast(x), cast(y), cast_mut(z))
+            }
+
+            fn add_vec_inplace(x: &mut [Self], y: &[Self]) {
+                <$ark>::add_vec_inplace(cast_mut(x), cast(y))
+            }
+
+            fn add_vec_inplace_par(x: &mut [Self], y: &[Self]) {
+                <$ark>::add_vec_inplace_par(cast_mut(x), cast(y))
+            }
+
+            fn sub_vec(x: &[Self], y: &[Self], z: &mut [Self]) {
+                <$ark>::sub_vec(cast(x), cast(y), cast_mut(z))
+            }
+
+            fn sub_vec_par(x: &[Self], y: &[Self], z: &mut [Self]) {
+                <$ark>::sub_vec_par(cast(x), cast(y), cast_mut(z))
+            }
+
+            fn sub_vec_inplace(x: &mut [Self], y: &[Self]) {
+                <$ark>::sub_vec_inplace(cast_mut(x), cast(y))
+            }
+
+            fn sub_vec_inplace_par(x: &mut [Self], y: &[Self]) {
+                <$ark>::sub_vec_inplace_par(cast_mut(x), cast(y))
+            }
+
+            fn mul_vec(x: &[Self], y: &[Self], z: &mut [Self]) {
+                <$ark>::mul_vec(cast(x), cast(y), cast_mut(z))
+            }
+
+            fn mul_vec_par(x: &[Self], y: &[Self], z: &mut [Self]) {
+                <$ark>::mul_vec_par(cast(x), cast(y), cast_mut(z))
+            }
+
+            fn mul_vec_inplace(x: &mut [Self], y: &[Self]) {
+                <$ark>::mul_vec_inplace(cast_mut(x), cast(y))
+            }
+
+            fn mul_vec_inplace_par(x: &mut [Self], y: &[Self]) {
+                <$ark>::mul_vec_inplace_par(cast_mut(x), cast(y))
+            }
+
+            fn sum_vec(x: &[Self]) -> Self {
+                cast_one(<$ark>::sum_vec(cast(x)))
+            }
+
+            fn sum_vec_par(x: &[Self]) -> Self {
+                cast_one(<$ark>::sum_vec_par(cast(x)))
+            }
+
+            fn inner_product(x: &[Self], y: &[Self]) -> Self {
+                cast_one(<$ark>::inner_product(cast(x), cast(y)))
+            }
+
+            fn inner_product_par(x: &[Self], y: &[Self]) -> Self {
+                cast_one(<$ark>::inner_product_par(cast(x), cast(y)))
+            }
+        }
+    };
+}
+
+impl_via_ark!(halo2curves::bn256::Fr, ark_bn254::Fr);
+impl_via_ark!(halo2curves::bn256::Fq, ark_bn254::Fq);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ext::VecFieldExt;
+    use halo2curves::ff::Field;
+    use rand::rngs::OsRng;
+
+    fn rand_halo2<F: Field>(len: usize) -> Vec<F> {
+        (0..len).map(|_| F::random(OsRng)).collect()
+    }
+
+    #[test]
+    fn halo2_layout_matches_ark() {
+        use ark_std::One;
+
+        let one: ark_bn254::Fr = cast_one(halo2curves::bn256::Fr::ONE);
+        assert_eq!(one, ark_bn254::Fr::one());
+        let one: ark_bn254::Fq = cast_one(halo2curves::bn256::Fq::ONE);
+        assert_eq!(one, ark_bn254::Fq::one());
+
+        let x = halo2curves::bn256::Fr::from(123_456_789u64);
+        let ark_x: ark_bn254::Fr = cast_one(x);
+        assert_eq!(ark_x, ark_bn254::Fr::from(123_456_789u64));
+    }
+
+    fn parity<F: Field + VectorizedField>() {
+        for len in [0, 1, 17, 1_003] {
+            let x: Vec<F> = rand_halo2(len);
+            let y: Vec<F> = rand_halo2(len);
+            let mut z = vec![F::ZERO; len];
+
+            x.add_into(&y, &mut z);
+            assert!(z
+                .iter()
+                .zip(x.iter().zip(&y))
+                .all(|(z, (x, y))| *z == *x + y));
+            x.par_sub_into(&y, &mut z);
+            assert!(z
+                .iter()
+                .zip(x.iter().zip(&y))
+                .all(|(z, (x, y))| *z == *x - y));
+            x.mul_into(&y, &mut z);
+            assert!(z
+                .iter()
+                .zip(x.iter().zip(&y))
+                .all(|(z, (x, y))| *z == *x * y));
+
+            let mut w = x.clone();
+            w.par_mul_assign_vec(&y);
+            assert_eq!(w, z);
+
+            assert_eq!(x.sum_simd(), x.iter().sum::<F>());
+            assert_eq!(x.par_sum_simd(), x.iter().sum::<F>());
+            let dot = x.iter().zip(&y).map(|(x, y)| *x * y).sum::<F>();
+            assert_eq!(x.dot(&y), dot);
+            assert_eq!(x.par_dot(&y), dot);
+        }
+    }
+
+    #[test]
+    fn parity_halo2() {
+        parity::<halo2curves::bn256::Fr>();
+        parity::<halo2curves::bn256::Fq>();
+    }
+}
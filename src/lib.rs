@@ -1,357 +1,117 @@
-use std::{
-    fs::{File, OpenOptions},
-    io::{BufReader, BufWriter, Read},
-};
-
-use ark_bn254::Fr;
-use ark_ff::PrimeField;
-use ark_std::Zero;
-
-use rayon::prelude::*;
-
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(all(feature = "ark", not(any(feature = "ark-0_4", feature = "ark-0_5"))))]
+compile_error!("the `ark` feature needs an arkworks release: enable `ark-0_4` or `ark-0_5`");
+#[cfg(all(feature = "ark-0_4", feature = "ark-0_5"))]
+compile_error!("`ark-0_4` and `ark-0_5` are mutually exclusive");
+
+// The 0.5 crates under the names the rest of the crate uses.
+#[cfg(feature = "ark-0_5")]
+extern crate ark_bls12_381_0_5 as ark_bls12_381;
+#[cfg(feature = "ark-0_5")]
+extern crate ark_bn254_0_5 as ark_bn254;
+#[cfg(feature = "ark-0_5")]
+extern crate ark_ff_0_5 as ark_ff;
+#[cfg(feature = "ark-0_5")]
+extern crate ark_serialize_0_5 as ark_serialize;
+#[cfg(feature = "ark-0_5")]
+extern crate ark_std_0_5 as ark_std;
+
+#[cfg(all(feature = "ark", any(test, feature = "parallel")))]
 mod ark;
 mod assembly;
+#[cfg(all(feature = "ark", feature = "parallel"))]
 pub mod benchmarks;
+#[cfg(feature = "ark")]
+mod bls12_381;
+#[cfg(feature = "ark")]
+mod bn254;
+#[cfg(feature = "parallel")]
+pub mod calibrate;
+#[cfg(feature = "std")]
+pub mod capi;
 mod constants;
+#[cfg(all(feature = "ark", feature = "parallel"))]
+mod context;
+pub mod expr;
+mod ext;
+mod field;
+mod field_vec;
+#[cfg(feature = "ark")]
+mod format;
+#[cfg(feature = "ark")]
+mod fq;
+#[cfg(feature = "halo2curves")]
+mod halo2;
+pub mod limbs;
+pub mod limbs384;
+#[cfg(all(unix, feature = "std"))]
+mod mmap;
+mod par;
+#[cfg(feature = "ark")]
+mod poly;
+pub mod schedule;
+#[cfg(feature = "ark")]
+mod serialize;
+#[cfg(feature = "ark")]
+mod sparse;
+#[cfg(feature = "ark")]
 mod utils;
-
-use crate::assembly::{modadd256, modip256_mont, modmul256_mont, modsub256, modsum256};
-
-const PAR_CHUNK_SIZE: usize = 128;
-
-pub fn add_vec_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
-    assert_eq!(x.len(), y.len());
-
-    unsafe {
-        modadd256(
-            z.as_mut_ptr() as *mut u64,
-            x.as_ptr() as *const u64,
-            y.as_ptr() as *const u64,
-            x.len() as u64,
-            constants::BN254_FR.as_ptr(),
-        )
-    }
-}
-
-pub fn add_vec_par_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
-    let len = x.len();
-    assert_eq!(y.len(), len);
-    assert_eq!(z.len(), len);
-
-    let chunk_size = std::cmp::min(len, PAR_CHUNK_SIZE);
-
-    x.par_chunks(chunk_size)
-        .zip(y.par_chunks(chunk_size))
-        .zip(z.par_chunks_mut(chunk_size))
-        .for_each(|((x_chunk, y_chunk), z_chunk)| {
-            add_vec_bn254(x_chunk, y_chunk, z_chunk);
-        });
-}
-
-pub fn add_vec_inplace_bn254(x: &mut [Fr], y: &[Fr]) {
-    assert_eq!(x.len(), y.len());
-
-    unsafe {
-        modadd256(
-            x.as_mut_ptr() as *mut u64,
-            x.as_ptr() as *const u64,
-            y.as_ptr() as *const u64,
-            x.len() as u64,
-            constants::BN254_FR.as_ptr(),
-        )
-    }
-}
-
-pub fn sub_vec_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
-    assert_eq!(x.len(), y.len());
-
-    unsafe {
-        modsub256(
-            z.as_mut_ptr() as *mut u64,
-            x.as_ptr() as *const u64,
-            y.as_ptr() as *const u64,
-            x.len() as u64,
-            constants::BN254_FR.as_ptr(),
-        )
-    }
-}
-
-pub fn sub_vec_par_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
-    let len = x.len();
-    assert_eq!(y.len(), len);
-    assert_eq!(z.len(), len);
-
-    let chunk_size = std::cmp::min(len, PAR_CHUNK_SIZE);
-
-    x.par_chunks(chunk_size)
-        .zip(y.par_chunks(chunk_size))
-        .zip(z.par_chunks_mut(chunk_size))
-        .for_each(|((x_chunk, y_chunk), z_chunk)| {
-            sub_vec_bn254(x_chunk, y_chunk, z_chunk);
-        });
-}
-
-pub fn sub_vec_inplace_bn254(x: &mut [Fr], y: &[Fr]) {
-    assert_eq!(x.len(), y.len());
-
-    unsafe {
-        modsub256(
-            x.as_mut_ptr() as *mut u64,
-            x.as_ptr() as *const u64,
-            y.as_ptr() as *const u64,
-            x.len() as u64,
-            constants::BN254_FR.as_ptr(),
-        )
-    }
-}
-
-pub fn sum_vec_bn254(x: &[Fr]) -> Fr {
-    let mut result = Fr::zero();
-    let simd_x = x.as_ptr() as *const u64;
-    let simd_result = result.0 .0.as_mut_ptr() as *mut u64;
-
-    unsafe {
-        modsum256(
-            simd_result,
-            simd_x,
-            x.len() as u32,
-            constants::BN254_FR.as_ptr(),
-        );
-    }
-
-    result
-}
-
-pub fn sum_vec_par_bn254(x: &[Fr]) -> Fr {
-    let chunk_size = std::cmp::min(x.len(), PAR_CHUNK_SIZE);
-
-    x.par_chunks(chunk_size)
-        .map(|chunk_x| sum_vec_bn254(chunk_x))
-        .sum::<Fr>()
-}
-
-pub fn mul_vec_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
-    let len = x.len();
-    assert_eq!(len, y.len());
-    assert_eq!(len, z.len());
-
-    let simd_x = x.as_ptr() as *const u64;
-    let simd_y = y.as_ptr() as *const u64;
-    let simd_z = z.as_mut_ptr() as *mut u64;
-
-    unsafe {
-        modmul256_mont(
-            simd_z,
-            simd_x,
-            simd_y,
-            len as u64,
-            constants::BN254_FR.as_ptr(),
-        );
-    }
-}
-
-pub fn mul_vec_par_bn254(x: &[Fr], y: &[Fr], z: &mut [Fr]) {
-    let len = x.len();
-    assert_eq!(y.len(), len);
-    assert_eq!(z.len(), len);
-
-    let chunk_size = std::cmp::min(len, PAR_CHUNK_SIZE);
-
-    x.par_chunks(chunk_size)
-        .zip(y.par_chunks(chunk_size))
-        .zip(z.par_chunks_mut(chunk_size))
-        .for_each(|((xi, yi), zi)| {
-            mul_vec_bn254(xi, yi, zi);
-        });
-}
-
-pub fn mul_vec_inplace_bn254(x: &mut [Fr], y: &[Fr]) {
-    let len = x.len();
-    assert_eq!(len, y.len());
-
-    let simd_x = x.as_ptr() as *const u64;
-    let simd_y = y.as_ptr() as *const u64;
-    let simd_z = x.as_mut_ptr() as *mut u64;
-
-    unsafe {
-        modmul256_mont(
-            simd_z,
-            simd_x,
-            simd_y,
-            len as u64,
-            constants::BN254_FR.as_ptr(),
-        );
-    }
-}
-
-pub fn inner_product_bn254(x: &[Fr], y: &[Fr]) -> Fr {
-    assert_eq!(x.len(), y.len());
-
-    let simd_x = x.as_ptr() as *const u64;
-    let simd_y = y.as_ptr() as *const u64;
-    let mut collect = Fr::zero();
-    let simd_z = collect.0 .0.as_mut_ptr() as *mut u64;
-
-    let simd_len: u32 = x.len().try_into().unwrap();
-
-    unsafe {
-        modip256_mont(
-            simd_z,
-            simd_x,
-            simd_y,
-            simd_len,
-            constants::BN254_FR.as_ptr(),
-        );
-    }
-    collect
-}
-
-pub fn inner_product_par_bn254(x: &[Fr], y: &[Fr]) -> Fr {
-    assert_eq!(x.len(), y.len());
-    let chunk_size = std::cmp::min(x.len(), PAR_CHUNK_SIZE);
-
-    x.par_chunks(chunk_size)
-        .zip(y.par_chunks(chunk_size))
-        .map(|(chunk_x, chunk_y)| inner_product_bn254(chunk_x, chunk_y))
-        .sum::<Fr>()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::ark::*;
-    use crate::utils::rand_vec;
-    use ark_std::Zero;
-
-    #[test]
-    fn parallel_parity_vec_mul() {
-        const NUM_OPS: usize = 10_000;
-        let x: Vec<Fr> = rand_vec(NUM_OPS);
-        let y: Vec<Fr> = rand_vec(NUM_OPS);
-        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
-
-        let x_par = x.clone();
-        let y_par = y.clone();
-        let mut z_par = z.clone();
-
-        mul_vec_bn254(&x, &y, &mut z);
-        mul_vec_par_bn254(&x_par, &y_par, &mut z_par);
-        assert_eq!(z, z_par);
-    }
-
-    #[test]
-    fn parallel_parity_inner_product() {
-        const NUM_OPS: usize = 10_000;
-        let x: Vec<Fr> = rand_vec(NUM_OPS);
-        let y: Vec<Fr> = rand_vec(NUM_OPS);
-
-        let z = inner_product_bn254(&x, &y);
-        let par_z = inner_product_par_bn254(&x, &y);
-        assert_eq!(z, par_z);
-    }
-
-    #[test]
-    fn parallel_parity_vec_add() {
-        const NUM_OPS: usize = 10_000;
-        let x: Vec<Fr> = rand_vec(NUM_OPS);
-        let y: Vec<Fr> = rand_vec(NUM_OPS);
-        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
-
-        let x_par = x.clone();
-        let y_par = y.clone();
-        let mut z_par = z.clone();
-
-        add_vec_bn254(&x, &y, &mut z);
-        add_vec_par_bn254(&x_par, &y_par, &mut z_par);
-        assert_eq!(z, z_par);
-    }
-
-    #[test]
-    fn parallel_parity_vec_sub() {
-        const NUM_OPS: usize = 10_000;
-        let x: Vec<Fr> = rand_vec(NUM_OPS);
-        let y: Vec<Fr> = rand_vec(NUM_OPS);
-        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
-
-        let x_par = x.clone();
-        let y_par = y.clone();
-        let mut z_par = z.clone();
-
-        sub_vec_bn254(&x, &y, &mut z);
-        sub_vec_par_bn254(&x_par, &y_par, &mut z_par);
-        assert_eq!(z, z_par);
-    }
-
-    #[test]
-    fn parallel_parity_vec_sum() {
-        const NUM_OPS: usize = 10_000;
-        let x: Vec<Fr> = rand_vec(NUM_OPS);
-
-        let x_par = x.clone();
-
-        let z = sum_vec_bn254(&x);
-        let z_par = sum_vec_par_bn254(&x_par);
-        assert_eq!(z, z_par);
-    }
-
-    #[test]
-    fn parity_ark_mul() {
-        const NUM_OPS: usize = 5;
-        let x: Vec<Fr> = rand_vec(NUM_OPS);
-        let y: Vec<Fr> = rand_vec(NUM_OPS);
-        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
-        let mut ark_z = z.clone();
-
-        ark_batch_mul(&x, &y, &mut ark_z);
-        mul_vec_bn254(&x, &y, &mut z);
-        assert_eq!(z, ark_z);
-    }
-
-    #[test]
-    fn parity_ark_add() {
-        const NUM_OPS: usize = 5;
-        let x: Vec<Fr> = rand_vec(NUM_OPS);
-        let y: Vec<Fr> = rand_vec(NUM_OPS);
-        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
-        let mut ark_z = z.clone();
-
-        ark_batch_add(&x, &y, &mut ark_z);
-        add_vec_bn254(&x, &y, &mut z);
-        assert_eq!(z, ark_z);
-    }
-
-    #[test]
-    fn parity_ark_sub() {
-        const NUM_OPS: usize = 5;
-        let x: Vec<Fr> = rand_vec(NUM_OPS);
-        let y: Vec<Fr> = rand_vec(NUM_OPS);
-        let mut z: Vec<Fr> = vec![Fr::zero(); NUM_OPS];
-        let mut ark_z = z.clone();
-
-        ark_batch_sub(&x, &y, &mut ark_z);
-        sub_vec_bn254(&x, &y, &mut z);
-        assert_eq!(z, ark_z);
-    }
-
-    #[test]
-    fn parity_ark_inner_product() {
-        const NUM_OPS: usize = 500;
-        let x: Vec<Fr> = rand_vec(NUM_OPS);
-        let y: Vec<Fr> = rand_vec(NUM_OPS);
-
-        let ark_z = ark_inner_product(&x, &y);
-        let z = inner_product_bn254(&x, &y);
-        assert_eq!(z, ark_z);
-    }
-
-    #[test]
-    fn parity_ark_sum() {
-        const NUM_OPS: usize = 5;
-        let x: Vec<Fr> = rand_vec(NUM_OPS);
-        let ark_x = x.clone();
-
-        let ark_z = ark_batch_sum(&ark_x);
-        let z = sum_vec_bn254(&x);
-        assert_eq!(z, ark_z);
-    }
-}
+mod validate;
+
+#[cfg(feature = "ark")]
+pub use crate::bls12_381::{
+    add_vec_bls12_381_fq, add_vec_inplace_bls12_381_fq, add_vec_inplace_par_bls12_381_fq,
+    add_vec_par_bls12_381_fq, canonical_to_montgomery_bls12_381_fq, inner_product_bls12_381_fq,
+    inner_product_par_bls12_381_fq, montgomery_to_canonical_bls12_381_fq, mul_vec_bls12_381_fq,
+    mul_vec_inplace_bls12_381_fq, mul_vec_inplace_par_bls12_381_fq, mul_vec_par_bls12_381_fq,
+    sub_vec_bls12_381_fq, sub_vec_inplace_bls12_381_fq, sub_vec_inplace_par_bls12_381_fq,
+    sub_vec_par_bls12_381_fq, sum_vec_bls12_381_fq, sum_vec_par_bls12_381_fq,
+};
+#[cfg(feature = "ark")]
+pub use crate::bn254::{
+    add_vec_auto_bn254, add_vec_bn254, add_vec_inplace_auto_bn254, add_vec_inplace_bn254,
+    add_vec_inplace_par_bn254, add_vec_nt_bn254, add_vec_nt_par_bn254, add_vec_par_bn254,
+    aligned_vec_bn254, aligned_vec_huge_bn254, canonical_to_montgomery_bn254,
+    canonical_to_montgomery_par_bn254, inner_product_auto_bn254, inner_product_bn254,
+    inner_product_par_bn254, linear_combination_bn254, linear_combination_par_bn254,
+    montgomery_to_canonical_bn254, montgomery_to_canonical_par_bn254, mul_vec_auto_bn254,
+    mul_vec_bn254, mul_vec_inplace_auto_bn254, mul_vec_inplace_bn254, mul_vec_inplace_par_bn254,
+    mul_vec_nt_bn254, mul_vec_nt_par_bn254, mul_vec_par_bn254, sub_vec_auto_bn254, sub_vec_bn254,
+    sub_vec_inplace_auto_bn254, sub_vec_inplace_bn254, sub_vec_inplace_par_bn254, sub_vec_nt_bn254,
+    sub_vec_nt_par_bn254, sub_vec_par_bn254, sub_vec_rev_inplace_bn254,
+    sub_vec_rev_inplace_par_bn254, sum_vec_auto_bn254, sum_vec_bn254, sum_vec_par_bn254,
+    sum_vectors_bn254, sum_vectors_par_bn254, validate_canonical_bn254,
+    validate_canonical_par_bn254,
+};
+#[cfg(all(feature = "ark", feature = "parallel"))]
+pub use crate::context::ParContext;
+pub use crate::ext::VecFieldExt;
+pub use crate::field::VectorizedField;
+pub use crate::field_vec::{Backing, FieldVec, FIELD_VEC_ALIGN};
+#[cfg(feature = "ark")]
+pub use crate::format::{
+    load_vec_bn254, read_vec_bn254, save_vec_bn254, write_vec_bn254, Representation, FORMAT_VERSION,
+};
+#[cfg(feature = "ark")]
+pub use crate::fq::{
+    add_vec_bn254_fq, add_vec_inplace_bn254_fq, add_vec_inplace_par_bn254_fq, add_vec_par_bn254_fq,
+    inner_product_bn254_fq, inner_product_par_bn254_fq, mul_vec_bn254_fq, mul_vec_inplace_bn254_fq,
+    mul_vec_inplace_par_bn254_fq, mul_vec_par_bn254_fq, sub_vec_bn254_fq, sub_vec_inplace_bn254_fq,
+    sub_vec_inplace_par_bn254_fq, sub_vec_par_bn254_fq, sum_vec_bn254_fq, sum_vec_par_bn254_fq,
+};
+pub use crate::limbs::Modulus;
+pub use crate::limbs384::Modulus384;
+#[cfg(all(unix, feature = "std"))]
+pub use crate::mmap::{MmapFieldVec, MMAP_WINDOW_LEN};
+#[cfg(feature = "ark")]
+pub use crate::poly::{
+    divide_by_linear_bn254, divide_by_linear_par_bn254, divide_many_by_linear_bn254,
+    poly_eval_bn254, poly_eval_many_bn254, poly_eval_par_bn254,
+};
+#[cfg(feature = "ark")]
+pub use crate::serialize::{deserialize_compressed_bn254, serialize_compressed_bn254};
+#[cfg(feature = "ark")]
+pub use crate::sparse::{spmv_bn254, spmv_par_bn254, CsrMatrix};
@@ -0,0 +1,921 @@
+//! The kernels on raw limbs, independent of any field library.
+//!
+//! Elements are `[u64; 4]` little-endian limbs in Montgomery form, `x * 2^256 mod m`, each below
+//! the modulus. Every operation takes the [`Modulus`] to reduce by, so any field of 225 to 256
+//! bits works, and any library that stores elements this way can cast its slices and call these
+//! directly. The arkworks functions (`*_bn254`, `*_bn254_fq`) are thin wrappers over this module.
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    assembly::{
+        modadd256, modadd256_nt, modip256_mont, modlc256_mont, modmul256_mont, modmul256_mont_nt,
+        modsub256, modsub256_nt, modsum256, modvsum256,
+    },
+    constants,
+    par::*,
+    schedule::{self, VecOp},
+    validate::{debug_assert_below, find_non_canonical, find_non_canonical_par},
+    FIELD_VEC_ALIGN,
+};
+
+/// An odd modulus of 225 to 256 bits, with the constants the kernels derive from it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Modulus {
+    /// The modulus, `-m^-1 mod 2^64` and the Barrett constant `floor(2^288 / m)`, the layout the
+    /// assembly reads.
+    words: [u64; 6],
+    /// `2^512 mod m`, which moves canonical residues into Montgomery form.
+    r2: [u64; 4],
+}
+
+/// `a >= b` on little-endian limbs.
+const fn geq<const N: usize>(a: &[u64; N], b: &[u64; N]) -> bool {
+    let mut i = N;
+    while i > 0 {
+        i -= 1;
+        if a[i] != b[i] {
+            return a[i] > b[i];
+        }
+    }
+    true
+}
+
+/// `a - b mod 2^(64 N)`.
+const fn wrapping_sub<const N: usize>(a: &[u64; N], b: &[u64; N]) -> [u64; N] {
+    let mut z = [0; N];
+    let mut borrow = false;
+    let mut i = 0;
+    while i < N {
+        let (d, b1) = a[i].overflowing_sub(b[i]);
+        let (d, b2) = d.overflowing_sub(borrow as u64);
+        z[i] = d;
+        borrow = b1 || b2;
+        i += 1;
+    }
+    z
+}
+
+/// `(2a + bit) mod m` for `a < m`, returning whether it wrapped, i.e. the quotient bit.
+pub(crate) const fn double_mod<const N: usize>(
+    a: &[u64; N],
+    bit: bool,
+    m: &[u64; N],
+) -> ([u64; N], bool) {
+    let mut z = [0; N];
+    let mut carry = bit as u64;
+    let mut i = 0;
+    while i < N {
+        z[i] = (a[i] << 1) | carry;
+        carry = a[i] >> 63;
+        i += 1;
+    }
+    // The true value is below 2m, so one subtraction reduces it.
+    if carry != 0 || geq(&z, m) {
+        (wrapping_sub(&z, m), true)
+    } else {
+        (z, false)
+    }
+}
+
+/// `-m0^-1 mod 2^64` for odd `m0`, the Montgomery reduction factor.
+pub(crate) const fn neg_inv(m0: u64) -> u64 {
+    // Newton's iteration doubles the correct low bits of m^-1 mod 2^64, from 1 bit.
+    let mut inv = 1u64;
+    let mut i = 0;
+    while i < 6 {
+        inv = inv.wrapping_mul(2u64.wrapping_sub(m0.wrapping_mul(inv)));
+        i += 1;
+    }
+    inv.wrapping_neg()
+}
+
+impl Modulus {
+    pub const BN254_FR: Modulus = Modulus::new(constants::BN254_FR_MODULUS);
+    pub const BN254_FQ: Modulus = Modulus::new(constants::BN254_FQ_MODULUS);
+
+    /// Derives the kernel constants for `m`, given as little-endian limbs.
+    ///
+    /// Panics unless `m` is odd and between 2^224 and 2^256, the range the kernels support.
+    pub const fn new(m: [u64; 4]) -> Modulus {
+        assert!(m[0] & 1 == 1, "modulus must be odd");
+        assert!(m[3] >> 32 != 0, "modulus must have more than 224 bits");
+
+        let inv = neg_inv(m[0]);
+
+        // Long division of 2^288 by m, one bit at a time. The quotient fits in 64 bits because
+        // m > 2^224.
+        let mut rem = [0; 4];
+        let mut mu = 0u64;
+        let mut bit = 289;
+        while bit > 0 {
+            bit -= 1;
+            let (r, q) = double_mod(&rem, bit == 288, &m);
+            rem = r;
+            if q {
+                mu |= 1 << bit;
+            }
+        }
+
+        // 1 doubled 512 times.
+        let mut r2 = [1, 0, 0, 0];
+        let mut i = 0;
+        while i < 512 {
+            r2 = double_mod(&r2, false, &m).0;
+            i += 1;
+        }
+
+        Modulus {
+            words: [m[0], m[1], m[2], m[3], inv, mu],
+            r2,
+        }
+    }
+
+    /// The modulus as little-endian limbs.
+    pub const fn modulus(&self) -> [u64; 4] {
+        [self.words[0], self.words[1], self.words[2], self.words[3]]
+    }
+
+    /// `R^2 mod m` for `R = 2^256`, the Montgomery form of `R`.
+    pub const fn r2(&self) -> [u64; 4] {
+        self.r2
+    }
+
+    fn as_ptr(&self) -> *const u64 {
+        self.words.as_ptr()
+    }
+
+    #[inline]
+    #[track_caller]
+    fn debug_assert_canonical(&self, x: &[[u64; 4]]) {
+        debug_assert_below(x, &self.modulus());
+    }
+}
+
+/// `z = x + y`.
+pub fn add(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    assert_eq!(x.len(), y.len());
+    assert_eq!(x.len(), z.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe {
+        modadd256(
+            z.as_mut_ptr() as *mut u64,
+            x.as_ptr() as *const u64,
+            y.as_ptr() as *const u64,
+            x.len() as u64,
+            m.as_ptr(),
+        )
+    }
+}
+
+pub fn add_par(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+    assert_eq!(z.len(), len);
+
+    schedule::install(VecOp::Add, || {
+        let chunk_size = schedule::chunk_size(VecOp::Add, len);
+
+        x.par_chunks(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .zip(z.par_chunks_mut(chunk_size))
+            .for_each(|((xi, yi), zi)| add(m, xi, yi, zi));
+    })
+}
+
+/// `x += y`.
+pub fn add_inplace(m: &Modulus, x: &mut [[u64; 4]], y: &[[u64; 4]]) {
+    assert_eq!(x.len(), y.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe {
+        modadd256(
+            x.as_mut_ptr() as *mut u64,
+            x.as_ptr() as *const u64,
+            y.as_ptr() as *const u64,
+            x.len() as u64,
+            m.as_ptr(),
+        )
+    }
+}
+
+pub fn add_inplace_par(m: &Modulus, x: &mut [[u64; 4]], y: &[[u64; 4]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+
+    schedule::install(VecOp::Add, || {
+        let chunk_size = schedule::chunk_size(VecOp::Add, len);
+
+        x.par_chunks_mut(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .for_each(|(xi, yi)| add_inplace(m, xi, yi));
+    })
+}
+
+/// `z = x + y` written with non-temporal stores, which skip the read-for-ownership of `z`.
+/// Faster than [`add`] when `z` is far larger than the last-level cache and not read again
+/// soon, slower otherwise.
+pub fn add_nt(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    assert_eq!(x.len(), y.len());
+    assert_eq!(x.len(), z.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe {
+        modadd256_nt(
+            z.as_mut_ptr() as *mut u64,
+            x.as_ptr() as *const u64,
+            y.as_ptr() as *const u64,
+            x.len() as u64,
+            m.as_ptr(),
+        )
+    }
+}
+
+pub fn add_nt_par(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+    assert_eq!(z.len(), len);
+
+    schedule::install(VecOp::Add, || {
+        let chunk_size = schedule::chunk_size(VecOp::Add, len);
+
+        x.par_chunks(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .zip(z.par_chunks_mut(chunk_size))
+            .for_each(|((xi, yi), zi)| add_nt(m, xi, yi, zi));
+    })
+}
+
+/// `z = x - y`.
+pub fn sub(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    assert_eq!(x.len(), y.len());
+    assert_eq!(x.len(), z.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe {
+        modsub256(
+            z.as_mut_ptr() as *mut u64,
+            x.as_ptr() as *const u64,
+            y.as_ptr() as *const u64,
+            x.len() as u64,
+            m.as_ptr(),
+        )
+    }
+}
+
+pub fn sub_par(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+    assert_eq!(z.len(), len);
+
+    schedule::install(VecOp::Sub, || {
+        let chunk_size = schedule::chunk_size(VecOp::Sub, len);
+
+        x.par_chunks(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .zip(z.par_chunks_mut(chunk_size))
+            .for_each(|((xi, yi), zi)| sub(m, xi, yi, zi));
+    })
+}
+
+/// `x -= y`.
+pub fn sub_inplace(m: &Modulus, x: &mut [[u64; 4]], y: &[[u64; 4]]) {
+    assert_eq!(x.len(), y.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe {
+        modsub256(
+            x.as_mut_ptr() as *mut u64,
+            x.as_ptr() as *const u64,
+            y.as_ptr() as *const u64,
+            x.len() as u64,
+            m.as_ptr(),
+        )
+    }
+}
+
+pub fn sub_inplace_par(m: &Modulus, x: &mut [[u64; 4]], y: &[[u64; 4]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+
+    schedule::install(VecOp::Sub, || {
+        let chunk_size = schedule::chunk_size(VecOp::Sub, len);
+
+        x.par_chunks_mut(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .for_each(|(xi, yi)| sub_inplace(m, xi, yi));
+    })
+}
+
+/// Reverse subtraction `y = x - y`, writing into `y`.
+pub fn sub_rev_inplace(m: &Modulus, x: &[[u64; 4]], y: &mut [[u64; 4]]) {
+    assert_eq!(x.len(), y.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe {
+        modsub256(
+            y.as_mut_ptr() as *mut u64,
+            x.as_ptr() as *const u64,
+            y.as_ptr() as *const u64,
+            x.len() as u64,
+            m.as_ptr(),
+        )
+    }
+}
+
+pub fn sub_rev_inplace_par(m: &Modulus, x: &[[u64; 4]], y: &mut [[u64; 4]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+
+    schedule::install(VecOp::Sub, || {
+        let chunk_size = schedule::chunk_size(VecOp::Sub, len);
+
+        x.par_chunks(chunk_size)
+            .zip(y.par_chunks_mut(chunk_size))
+            .for_each(|(xi, yi)| sub_rev_inplace(m, xi, yi));
+    })
+}
+
+/// `z = x - y` written with non-temporal stores; see [`add_nt`].
+pub fn sub_nt(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    assert_eq!(x.len(), y.len());
+    assert_eq!(x.len(), z.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe {
+        modsub256_nt(
+            z.as_mut_ptr() as *mut u64,
+            x.as_ptr() as *const u64,
+            y.as_ptr() as *const u64,
+            x.len() as u64,
+            m.as_ptr(),
+        )
+    }
+}
+
+pub fn sub_nt_par(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+    assert_eq!(z.len(), len);
+
+    schedule::install(VecOp::Sub, || {
+        let chunk_size = schedule::chunk_size(VecOp::Sub, len);
+
+        x.par_chunks(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .zip(z.par_chunks_mut(chunk_size))
+            .for_each(|((xi, yi), zi)| sub_nt(m, xi, yi, zi));
+    })
+}
+
+/// Elements per iteration of the AVX-512 loop of `modmul256_mont`, which multiplies the leading
+/// `len % MUL_BLOCK` elements one at a time first.
+pub(crate) const MUL_BLOCK: usize = 16;
+
+/// Bytes per element.
+const ELEMENT: usize = core::mem::size_of::<[u64; 4]>();
+
+/// Runs `modmul256_mont` so that its AVX-512 loop reads whole 64-byte lines where possible.
+///
+/// When `x`, `y` and `z` sit at the same offset within a cache line, the call is split so that
+/// the vector loop starts on a line boundary and the leftover elements go through the scalar
+/// path in a second call. Otherwise every ZMM load could straddle two lines.
+unsafe fn modmul(
+    m: &Modulus,
+    z: *mut [u64; 4],
+    x: *const [u64; 4],
+    y: *const [u64; 4],
+    len: usize,
+) {
+    let call = |offset: usize, len: usize| {
+        modmul256_mont(
+            z.add(offset) as *mut u64,
+            x.add(offset) as *const u64,
+            y.add(offset) as *const u64,
+            len as u64,
+            m.as_ptr(),
+        )
+    };
+
+    let offset = x as usize % FIELD_VEC_ALIGN;
+    let same_offset = offset.is_multiple_of(ELEMENT)
+        && y as usize % FIELD_VEC_ALIGN == offset
+        && z as usize % FIELD_VEC_ALIGN == offset;
+    let head = (FIELD_VEC_ALIGN - offset) % FIELD_VEC_ALIGN / ELEMENT;
+
+    if !same_offset || len < head + MUL_BLOCK {
+        return call(0, len);
+    }
+
+    let split = head + (len - head) / MUL_BLOCK * MUL_BLOCK;
+    call(0, split);
+    if split < len {
+        call(split, len - split);
+    }
+}
+
+/// Runs `modmul256_mont_nt`, whose aligned streaming stores need the AVX-512 loop to start on
+/// a 64-byte line of `z`. Destinations that cannot be lined up go through the cached kernel.
+unsafe fn modmul_nt(
+    m: &Modulus,
+    z: *mut [u64; 4],
+    x: *const [u64; 4],
+    y: *const [u64; 4],
+    len: usize,
+) {
+    let call = |offset: usize, len: usize| {
+        modmul256_mont_nt(
+            z.add(offset) as *mut u64,
+            x.add(offset) as *const u64,
+            y.add(offset) as *const u64,
+            len as u64,
+            m.as_ptr(),
+        )
+    };
+
+    let offset = z as usize % FIELD_VEC_ALIGN;
+    if !offset.is_multiple_of(ELEMENT) {
+        return modmul(m, z, x, y, len);
+    }
+    let head = (FIELD_VEC_ALIGN - offset) % FIELD_VEC_ALIGN / ELEMENT;
+    if len <= head {
+        return call(0, len);
+    }
+
+    // The first call multiplies `head` elements one at a time and then runs its vector loop from
+    // an aligned line; the second has fewer than `MUL_BLOCK` elements and never reaches it.
+    let split = head + (len - head) / MUL_BLOCK * MUL_BLOCK;
+    call(0, split);
+    if split < len {
+        call(split, len - split);
+    }
+}
+
+/// `z = x * y`, the Montgomery product.
+pub fn mul(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    let len = x.len();
+    assert_eq!(len, y.len());
+    assert_eq!(len, z.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe { modmul(m, z.as_mut_ptr(), x.as_ptr(), y.as_ptr(), len) }
+}
+
+pub fn mul_par(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+    assert_eq!(z.len(), len);
+
+    schedule::install(VecOp::Mul, || {
+        let chunk_size = schedule::chunk_size(VecOp::Mul, len);
+
+        x.par_chunks(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .zip(z.par_chunks_mut(chunk_size))
+            .for_each(|((xi, yi), zi)| mul(m, xi, yi, zi));
+    })
+}
+
+/// `x *= y`.
+pub fn mul_inplace(m: &Modulus, x: &mut [[u64; 4]], y: &[[u64; 4]]) {
+    let len = x.len();
+    assert_eq!(len, y.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe { modmul(m, x.as_mut_ptr(), x.as_ptr(), y.as_ptr(), len) }
+}
+
+pub fn mul_inplace_par(m: &Modulus, x: &mut [[u64; 4]], y: &[[u64; 4]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+
+    schedule::install(VecOp::Mul, || {
+        let chunk_size = schedule::chunk_size(VecOp::Mul, len);
+
+        x.par_chunks_mut(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .for_each(|(xi, yi)| mul_inplace(m, xi, yi));
+    })
+}
+
+/// `z = x * y` written with non-temporal stores; see [`add_nt`]. `z` should start at a multiple
+/// of 32 bytes, as `FieldVec` and most allocators guarantee, to take the streaming path.
+pub fn mul_nt(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    let len = x.len();
+    assert_eq!(len, y.len());
+    assert_eq!(len, z.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe { modmul_nt(m, z.as_mut_ptr(), x.as_ptr(), y.as_ptr(), len) }
+}
+
+pub fn mul_nt_par(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+    assert_eq!(z.len(), len);
+
+    schedule::install(VecOp::Mul, || {
+        let chunk_size = schedule::chunk_size(VecOp::Mul, len);
+
+        x.par_chunks(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .zip(z.par_chunks_mut(chunk_size))
+            .for_each(|((xi, yi), zi)| mul_nt(m, xi, yi, zi));
+    })
+}
+
+/// `Σ x[i]`.
+pub fn sum(m: &Modulus, x: &[[u64; 4]]) -> [u64; 4] {
+    m.debug_assert_canonical(x);
+
+    let mut result = [0u64; 4];
+    unsafe {
+        modsum256(
+            result.as_mut_ptr(),
+            x.as_ptr() as *const u64,
+            x.len().try_into().unwrap(),
+            m.as_ptr(),
+        );
+    }
+    result
+}
+
+pub fn sum_par(m: &Modulus, x: &[[u64; 4]]) -> [u64; 4] {
+    schedule::install(VecOp::Sum, || {
+        let chunk_size = schedule::chunk_size(VecOp::Sum, x.len());
+
+        let partials: Vec<[u64; 4]> = x.par_chunks(chunk_size).map(|xi| sum(m, xi)).collect();
+        sum(m, &partials)
+    })
+}
+
+/// `Σ x[i] * y[i]`, accumulated unreduced and reduced once.
+pub fn inner_product(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]]) -> [u64; 4] {
+    assert_eq!(x.len(), y.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    let mut result = [0u64; 4];
+    unsafe {
+        modip256_mont(
+            result.as_mut_ptr(),
+            x.as_ptr() as *const u64,
+            y.as_ptr() as *const u64,
+            x.len().try_into().unwrap(),
+            m.as_ptr(),
+        );
+    }
+    result
+}
+
+pub fn inner_product_par(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]]) -> [u64; 4] {
+    assert_eq!(x.len(), y.len());
+    schedule::install(VecOp::InnerProduct, || {
+        let chunk_size = schedule::chunk_size(VecOp::InnerProduct, x.len());
+
+        let partials: Vec<[u64; 4]> = x
+            .par_chunks(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .map(|(xi, yi)| inner_product(m, xi, yi))
+            .collect();
+        sum(m, &partials)
+    })
+}
+
+pub fn add_auto(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    let par = schedule::should_parallelize(VecOp::Add, x.len());
+    match (par, schedule::should_stream(core::mem::size_of_val(z))) {
+        (false, false) => add(m, x, y, z),
+        (false, true) => add_nt(m, x, y, z),
+        (true, false) => add_par(m, x, y, z),
+        (true, true) => add_nt_par(m, x, y, z),
+    }
+}
+
+pub fn add_inplace_auto(m: &Modulus, x: &mut [[u64; 4]], y: &[[u64; 4]]) {
+    if schedule::should_parallelize(VecOp::Add, x.len()) {
+        add_inplace_par(m, x, y)
+    } else {
+        add_inplace(m, x, y)
+    }
+}
+
+pub fn sub_auto(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    let par = schedule::should_parallelize(VecOp::Sub, x.len());
+    match (par, schedule::should_stream(core::mem::size_of_val(z))) {
+        (false, false) => sub(m, x, y, z),
+        (false, true) => sub_nt(m, x, y, z),
+        (true, false) => sub_par(m, x, y, z),
+        (true, true) => sub_nt_par(m, x, y, z),
+    }
+}
+
+pub fn sub_inplace_auto(m: &Modulus, x: &mut [[u64; 4]], y: &[[u64; 4]]) {
+    if schedule::should_parallelize(VecOp::Sub, x.len()) {
+        sub_inplace_par(m, x, y)
+    } else {
+        sub_inplace(m, x, y)
+    }
+}
+
+pub fn mul_auto(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    let par = schedule::should_parallelize(VecOp::Mul, x.len());
+    match (par, schedule::should_stream(core::mem::size_of_val(z))) {
+        (false, false) => mul(m, x, y, z),
+        (false, true) => mul_nt(m, x, y, z),
+        (true, false) => mul_par(m, x, y, z),
+        (true, true) => mul_nt_par(m, x, y, z),
+    }
+}
+
+pub fn mul_inplace_auto(m: &Modulus, x: &mut [[u64; 4]], y: &[[u64; 4]]) {
+    if schedule::should_parallelize(VecOp::Mul, x.len()) {
+        mul_inplace_par(m, x, y)
+    } else {
+        mul_inplace(m, x, y)
+    }
+}
+
+pub fn sum_auto(m: &Modulus, x: &[[u64; 4]]) -> [u64; 4] {
+    if schedule::should_parallelize(VecOp::Sum, x.len()) {
+        sum_par(m, x)
+    } else {
+        sum(m, x)
+    }
+}
+
+pub fn inner_product_auto(m: &Modulus, x: &[[u64; 4]], y: &[[u64; 4]]) -> [u64; 4] {
+    if schedule::should_parallelize(VecOp::InnerProduct, x.len()) {
+        inner_product_par(m, x, y)
+    } else {
+        inner_product(m, x, y)
+    }
+}
+
+/// Computes `z[offset..offset + z.len()]` of `Σ_j r[j] · v[j]`.
+///
+/// `modlc256_mont` accumulates the partial products of all `k` terms unreduced for eight
+/// elements at a time and reduces each block once, so every vector is read once and `z`
+/// written once.
+fn linear_combination_block(
+    m: &Modulus,
+    r: &[[u64; 4]],
+    v: &[&[[u64; 4]]],
+    offset: usize,
+    z: &mut [[u64; 4]],
+) {
+    let len = z.len();
+    let ptrs: Vec<*const u64> = v
+        .iter()
+        .map(|vj| {
+            let vj = &vj[offset..offset + len];
+            m.debug_assert_canonical(vj);
+            vj.as_ptr() as *const u64
+        })
+        .collect();
+
+    unsafe {
+        modlc256_mont(
+            z.as_mut_ptr() as *mut u64,
+            r.as_ptr() as *const u64,
+            ptrs.as_ptr(),
+            r.len() as u32,
+            len as u64,
+            m.as_ptr(),
+        );
+    }
+}
+
+/// `z = Σ_j r[j] · v[j]`, for up to 2^27 vectors.
+pub fn linear_combination(m: &Modulus, r: &[[u64; 4]], v: &[&[[u64; 4]]], z: &mut [[u64; 4]]) {
+    assert_eq!(r.len(), v.len());
+    assert!(r.len() <= 1 << 27);
+    m.debug_assert_canonical(r);
+    for vj in v {
+        assert_eq!(vj.len(), z.len());
+    }
+
+    linear_combination_block(m, r, v, 0, z);
+}
+
+pub fn linear_combination_par(m: &Modulus, r: &[[u64; 4]], v: &[&[[u64; 4]]], z: &mut [[u64; 4]]) {
+    let len = z.len();
+    assert_eq!(r.len(), v.len());
+    assert!(r.len() <= 1 << 27);
+    m.debug_assert_canonical(r);
+    for vj in v {
+        assert_eq!(vj.len(), len);
+    }
+
+    schedule::install(VecOp::LinearCombination, || {
+        let chunk_size = schedule::chunk_size_scaled(VecOp::LinearCombination, len, r.len());
+
+        z.par_chunks_mut(chunk_size)
+            .enumerate()
+            .for_each(|(i, zi)| linear_combination_block(m, r, v, i * chunk_size, zi));
+    })
+}
+
+/// Computes `z[offset..offset + z.len()]` of `Σ_j v[j]`.
+///
+/// `modvsum256` adds up the 32-bit digits of all `k` vectors for eight elements at a time and
+/// reduces each block once, so every vector is read once and `z` written once.
+fn sum_vectors_block(m: &Modulus, v: &[&[[u64; 4]]], offset: usize, z: &mut [[u64; 4]]) {
+    let len = z.len();
+    let ptrs: Vec<*const u64> = v
+        .iter()
+        .map(|vj| {
+            let vj = &vj[offset..offset + len];
+            m.debug_assert_canonical(vj);
+            vj.as_ptr() as *const u64
+        })
+        .collect();
+
+    unsafe {
+        modvsum256(
+            z.as_mut_ptr() as *mut u64,
+            ptrs.as_ptr(),
+            v.len() as u32,
+            len as u64,
+            m.as_ptr(),
+        );
+    }
+}
+
+/// `z = Σ_j v[j]`, for up to 2^31 vectors.
+pub fn sum_vectors(m: &Modulus, v: &[&[[u64; 4]]], z: &mut [[u64; 4]]) {
+    assert!(v.len() <= 1 << 31);
+    for vj in v {
+        assert_eq!(vj.len(), z.len());
+    }
+
+    sum_vectors_block(m, v, 0, z);
+}
+
+pub fn sum_vectors_par(m: &Modulus, v: &[&[[u64; 4]]], z: &mut [[u64; 4]]) {
+    let len = z.len();
+    assert!(v.len() <= 1 << 31);
+    for vj in v {
+        assert_eq!(vj.len(), len);
+    }
+
+    schedule::install(VecOp::SumVectors, || {
+        let chunk_size = schedule::chunk_size_scaled(VecOp::SumVectors, len, v.len());
+
+        z.par_chunks_mut(chunk_size)
+            .enumerate()
+            .for_each(|(i, zi)| sum_vectors_block(m, v, i * chunk_size, zi));
+    })
+}
+
+/// Elements converted per `modmul256_mont` call, bounding the broadcast constant buffer.
+const CONVERT_BLOCK: usize = 1024;
+
+/// `z[i] = x[i] * c / R` through the Montgomery mul kernel.
+fn mul_by_constant(m: &Modulus, x: &[[u64; 4]], z: &mut [[u64; 4]], c: [u64; 4]) {
+    let broadcast = vec![c; core::cmp::min(x.len(), CONVERT_BLOCK)];
+    for (xi, zi) in x.chunks(CONVERT_BLOCK).zip(z.chunks_mut(CONVERT_BLOCK)) {
+        unsafe {
+            modmul256_mont(
+                zi.as_mut_ptr() as *mut u64,
+                xi.as_ptr() as *const u64,
+                broadcast.as_ptr() as *const u64,
+                xi.len() as u64,
+                m.as_ptr(),
+            )
+        }
+    }
+}
+
+/// Converts Montgomery-form elements to canonical residues.
+pub fn from_montgomery(m: &Modulus, x: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    assert_eq!(x.len(), z.len());
+    m.debug_assert_canonical(x);
+    mul_by_constant(m, x, z, [1, 0, 0, 0]);
+}
+
+pub fn from_montgomery_par(m: &Modulus, x: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    let len = x.len();
+    assert_eq!(z.len(), len);
+
+    schedule::install(VecOp::Mul, || {
+        let chunk_size = schedule::chunk_size(VecOp::Mul, len);
+
+        x.par_chunks(chunk_size)
+            .zip(z.par_chunks_mut(chunk_size))
+            .for_each(|(xi, zi)| from_montgomery(m, xi, zi));
+    })
+}
+
+/// Converts canonical residues, each below the modulus, to Montgomery form.
+pub fn to_montgomery(m: &Modulus, x: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    assert_eq!(x.len(), z.len());
+    m.debug_assert_canonical(x);
+    mul_by_constant(m, x, z, m.r2);
+}
+
+pub fn to_montgomery_par(m: &Modulus, x: &[[u64; 4]], z: &mut [[u64; 4]]) {
+    let len = x.len();
+    assert_eq!(z.len(), len);
+
+    schedule::install(VecOp::Mul, || {
+        let chunk_size = schedule::chunk_size(VecOp::Mul, len);
+
+        x.par_chunks(chunk_size)
+            .zip(z.par_chunks_mut(chunk_size))
+            .for_each(|(xi, zi)| to_montgomery(m, xi, zi));
+    })
+}
+
+/// Checks that every element of `x` is below the modulus, as the kernels require. Returns the
+/// index of the first element that is not.
+pub fn validate(m: &Modulus, x: &[[u64; 4]]) -> Result<(), usize> {
+    match find_non_canonical(x, &m.modulus()) {
+        Some(i) => Err(i),
+        None => Ok(()),
+    }
+}
+
+pub fn validate_par(m: &Modulus, x: &[[u64; 4]]) -> Result<(), usize> {
+    schedule::install(VecOp::Validate, || {
+        let chunk_size = schedule::chunk_size(VecOp::Validate, x.len());
+        match find_non_canonical_par(x, &m.modulus(), chunk_size) {
+            Some(i) => Err(i),
+            None => Ok(()),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Small values in Montgomery form, without any field library.
+    fn small(m: &Modulus, len: usize, seed: u64) -> Vec<[u64; 4]> {
+        let canonical: Vec<[u64; 4]> = (0..len as u64).map(|i| [i * seed + 1, 0, 0, 0]).collect();
+        let mut z = vec![[0; 4]; len];
+        to_montgomery(m, &canonical, &mut z);
+        z
+    }
+
+    #[test]
+    fn modulus_constants() {
+        assert_eq!(Modulus::BN254_FR.words, constants::BN254_FR);
+        assert_eq!(Modulus::BN254_FQ.words, constants::BN254_FP);
+
+        #[cfg(feature = "ark")]
+        {
+            use ark_ff::MontConfig;
+            assert_eq!(Modulus::BN254_FR.r2(), ark_bn254::FrConfig::R2.0);
+            assert_eq!(Modulus::BN254_FQ.r2(), ark_bn254::FqConfig::R2.0);
+        }
+    }
+
+    #[test]
+    fn limbs_small_values() {
+        const NUM_OPS: usize = 1_003;
+        for m in [&Modulus::BN254_FR, &Modulus::BN254_FQ] {
+            let x = small(m, NUM_OPS, 3);
+            let y = small(m, NUM_OPS, 5);
+            let mut z = vec![[0; 4]; NUM_OPS];
+            let mut canonical = vec![[0; 4]; NUM_OPS];
+
+            mul_par(m, &x, &y, &mut z);
+            add_inplace(m, &mut z, &x);
+            sub_rev_inplace(m, &y, &mut z);
+            from_montgomery_par(m, &z, &mut canonical);
+            for (i, zi) in canonical.iter().enumerate() {
+                let (xi, yi) = (3 * i as u64 + 1, 5 * i as u64 + 1);
+                // y - (x * y + x) wraps below zero.
+                let expected = wrapping_sub(&m.modulus(), &[xi * yi + xi - yi, 0, 0, 0]);
+                assert_eq!(*zi, expected);
+            }
+
+            let one = small(m, 1, 0);
+            let n = NUM_OPS as u64;
+            let mut total = [
+                sum_par(m, &x),
+                inner_product_par(m, &one.repeat(NUM_OPS), &y),
+            ];
+            from_montgomery(m, &total.clone(), &mut total);
+            assert_eq!(total[0], [n * (3 * (n - 1) / 2 + 1), 0, 0, 0]);
+            assert_eq!(total[1], [n * (5 * (n - 1) / 2 + 1), 0, 0, 0]);
+
+            assert_eq!(validate(m, &x), Ok(()));
+            let mut bad = y.clone();
+            bad[700] = m.modulus();
+            assert_eq!(validate_par(m, &bad), Err(700));
+        }
+    }
+}
@@ -0,0 +1,437 @@
+//! The 384-bit kernels on raw limbs, for fields too wide for [`crate::limbs`], such as the
+//! BLS12-381 base field.
+//!
+//! Elements are `[u64; 6]` little-endian limbs in Montgomery form, `x * 2^384 mod m`, each below
+//! the modulus. Every operation takes the [`Modulus384`] to reduce by, so any field of 321 to 383
+//! bits works. Every kernel here is scalar MULX/ADX code: unlike the 256-bit kernels there is no
+//! AVX-512 path, so BLS12-381 `Fq` gains nothing from AVX-512 hardware. The `_par` forms split
+//! them across rayon the same way as the 256-bit ones.
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    assembly::{modadd384, modip384_mont, modmul384_mont, modsub384, modsum384},
+    constants,
+    limbs::{double_mod, neg_inv},
+    par::*,
+    schedule::{self, VecOp},
+    validate::debug_assert_below_scalar,
+};
+
+/// An odd modulus of 321 to 383 bits, with the constants the kernels derive from it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Modulus384 {
+    /// The modulus and `-m^-1 mod 2^64`, the layout the assembly reads.
+    words: [u64; 7],
+    /// `2^768 mod m`, which moves canonical residues into Montgomery form.
+    r2: [u64; 6],
+}
+
+impl Modulus384 {
+    pub const BLS12_381_FQ: Modulus384 = Modulus384::new(constants::BLS12_381_FQ_MODULUS);
+
+    /// Derives the kernel constants for `m`, given as little-endian limbs.
+    ///
+    /// Panics unless `m` is odd and between 2^320 and 2^383. The kernels keep sums and
+    /// unreduced products below `2m` in six limbs, which needs the top bit clear.
+    pub const fn new(m: [u64; 6]) -> Modulus384 {
+        assert!(m[0] & 1 == 1, "modulus must be odd");
+        assert!(m[5] != 0, "modulus must have more than 320 bits");
+        assert!(m[5] >> 63 == 0, "modulus must be below 2^383");
+
+        // 1 doubled 768 times.
+        let mut r2 = [1, 0, 0, 0, 0, 0];
+        let mut i = 0;
+        while i < 768 {
+            r2 = double_mod(&r2, false, &m).0;
+            i += 1;
+        }
+
+        Modulus384 {
+            words: [m[0], m[1], m[2], m[3], m[4], m[5], neg_inv(m[0])],
+            r2,
+        }
+    }
+
+    /// The modulus as little-endian limbs.
+    pub const fn modulus(&self) -> [u64; 6] {
+        [
+            self.words[0],
+            self.words[1],
+            self.words[2],
+            self.words[3],
+            self.words[4],
+            self.words[5],
+        ]
+    }
+
+    /// `R^2 mod m` for `R = 2^384`, the Montgomery form of `R`.
+    pub const fn r2(&self) -> [u64; 6] {
+        self.r2
+    }
+
+    fn as_ptr(&self) -> *const u64 {
+        self.words.as_ptr()
+    }
+
+    #[inline]
+    #[track_caller]
+    fn debug_assert_canonical(&self, x: &[[u64; 6]]) {
+        debug_assert_below_scalar(x, &self.modulus());
+    }
+}
+
+/// `z = x + y`.
+pub fn add(m: &Modulus384, x: &[[u64; 6]], y: &[[u64; 6]], z: &mut [[u64; 6]]) {
+    assert_eq!(x.len(), y.len());
+    assert_eq!(x.len(), z.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe {
+        modadd384(
+            z.as_mut_ptr() as *mut u64,
+            x.as_ptr() as *const u64,
+            y.as_ptr() as *const u64,
+            x.len() as u64,
+            m.as_ptr(),
+        )
+    }
+}
+
+pub fn add_par(m: &Modulus384, x: &[[u64; 6]], y: &[[u64; 6]], z: &mut [[u64; 6]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+    assert_eq!(z.len(), len);
+
+    schedule::install(VecOp::Add, || {
+        let chunk_size = schedule::chunk_size(VecOp::Add, len);
+
+        x.par_chunks(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .zip(z.par_chunks_mut(chunk_size))
+            .for_each(|((xi, yi), zi)| add(m, xi, yi, zi));
+    })
+}
+
+/// `x += y`.
+pub fn add_inplace(m: &Modulus384, x: &mut [[u64; 6]], y: &[[u64; 6]]) {
+    assert_eq!(x.len(), y.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe {
+        modadd384(
+            x.as_mut_ptr() as *mut u64,
+            x.as_ptr() as *const u64,
+            y.as_ptr() as *const u64,
+            x.len() as u64,
+            m.as_ptr(),
+        )
+    }
+}
+
+pub fn add_inplace_par(m: &Modulus384, x: &mut [[u64; 6]], y: &[[u64; 6]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+
+    schedule::install(VecOp::Add, || {
+        let chunk_size = schedule::chunk_size(VecOp::Add, len);
+
+        x.par_chunks_mut(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .for_each(|(xi, yi)| add_inplace(m, xi, yi));
+    })
+}
+
+/// `z = x - y`.
+pub fn sub(m: &Modulus384, x: &[[u64; 6]], y: &[[u64; 6]], z: &mut [[u64; 6]]) {
+    assert_eq!(x.len(), y.len());
+    assert_eq!(x.len(), z.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe {
+        modsub384(
+            z.as_mut_ptr() as *mut u64,
+            x.as_ptr() as *const u64,
+            y.as_ptr() as *const u64,
+            x.len() as u64,
+            m.as_ptr(),
+        )
+    }
+}
+
+pub fn sub_par(m: &Modulus384, x: &[[u64; 6]], y: &[[u64; 6]], z: &mut [[u64; 6]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+    assert_eq!(z.len(), len);
+
+    schedule::install(VecOp::Sub, || {
+        let chunk_size = schedule::chunk_size(VecOp::Sub, len);
+
+        x.par_chunks(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .zip(z.par_chunks_mut(chunk_size))
+            .for_each(|((xi, yi), zi)| sub(m, xi, yi, zi));
+    })
+}
+
+/// `x -= y`.
+pub fn sub_inplace(m: &Modulus384, x: &mut [[u64; 6]], y: &[[u64; 6]]) {
+    assert_eq!(x.len(), y.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe {
+        modsub384(
+            x.as_mut_ptr() as *mut u64,
+            x.as_ptr() as *const u64,
+            y.as_ptr() as *const u64,
+            x.len() as u64,
+            m.as_ptr(),
+        )
+    }
+}
+
+pub fn sub_inplace_par(m: &Modulus384, x: &mut [[u64; 6]], y: &[[u64; 6]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+
+    schedule::install(VecOp::Sub, || {
+        let chunk_size = schedule::chunk_size(VecOp::Sub, len);
+
+        x.par_chunks_mut(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .for_each(|(xi, yi)| sub_inplace(m, xi, yi));
+    })
+}
+
+/// `z = x * y`, the Montgomery product.
+pub fn mul(m: &Modulus384, x: &[[u64; 6]], y: &[[u64; 6]], z: &mut [[u64; 6]]) {
+    assert_eq!(x.len(), y.len());
+    assert_eq!(x.len(), z.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe {
+        modmul384_mont(
+            z.as_mut_ptr() as *mut u64,
+            x.as_ptr() as *const u64,
+            y.as_ptr() as *const u64,
+            x.len() as u64,
+            m.as_ptr(),
+        )
+    }
+}
+
+pub fn mul_par(m: &Modulus384, x: &[[u64; 6]], y: &[[u64; 6]], z: &mut [[u64; 6]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+    assert_eq!(z.len(), len);
+
+    schedule::install(VecOp::Mul, || {
+        let chunk_size = schedule::chunk_size(VecOp::Mul, len);
+
+        x.par_chunks(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .zip(z.par_chunks_mut(chunk_size))
+            .for_each(|((xi, yi), zi)| mul(m, xi, yi, zi));
+    })
+}
+
+/// `x *= y`.
+pub fn mul_inplace(m: &Modulus384, x: &mut [[u64; 6]], y: &[[u64; 6]]) {
+    assert_eq!(x.len(), y.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    unsafe {
+        modmul384_mont(
+            x.as_mut_ptr() as *mut u64,
+            x.as_ptr() as *const u64,
+            y.as_ptr() as *const u64,
+            x.len() as u64,
+            m.as_ptr(),
+        )
+    }
+}
+
+pub fn mul_inplace_par(m: &Modulus384, x: &mut [[u64; 6]], y: &[[u64; 6]]) {
+    let len = x.len();
+    assert_eq!(y.len(), len);
+
+    schedule::install(VecOp::Mul, || {
+        let chunk_size = schedule::chunk_size(VecOp::Mul, len);
+
+        x.par_chunks_mut(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .for_each(|(xi, yi)| mul_inplace(m, xi, yi));
+    })
+}
+
+/// `Σ x[i]`.
+pub fn sum(m: &Modulus384, x: &[[u64; 6]]) -> [u64; 6] {
+    m.debug_assert_canonical(x);
+
+    let mut result = [0u64; 6];
+    unsafe {
+        modsum384(
+            result.as_mut_ptr(),
+            x.as_ptr() as *const u64,
+            x.len().try_into().unwrap(),
+            m.as_ptr(),
+        );
+    }
+    result
+}
+
+pub fn sum_par(m: &Modulus384, x: &[[u64; 6]]) -> [u64; 6] {
+    schedule::install(VecOp::Sum, || {
+        let chunk_size = schedule::chunk_size(VecOp::Sum, x.len());
+
+        let partials: Vec<[u64; 6]> = x.par_chunks(chunk_size).map(|xi| sum(m, xi)).collect();
+        sum(m, &partials)
+    })
+}
+
+/// `Σ x[i] * y[i]`.
+pub fn inner_product(m: &Modulus384, x: &[[u64; 6]], y: &[[u64; 6]]) -> [u64; 6] {
+    assert_eq!(x.len(), y.len());
+    m.debug_assert_canonical(x);
+    m.debug_assert_canonical(y);
+
+    let mut result = [0u64; 6];
+    unsafe {
+        modip384_mont(
+            result.as_mut_ptr(),
+            x.as_ptr() as *const u64,
+            y.as_ptr() as *const u64,
+            x.len().try_into().unwrap(),
+            m.as_ptr(),
+        );
+    }
+    result
+}
+
+pub fn inner_product_par(m: &Modulus384, x: &[[u64; 6]], y: &[[u64; 6]]) -> [u64; 6] {
+    assert_eq!(x.len(), y.len());
+    schedule::install(VecOp::InnerProduct, || {
+        let chunk_size = schedule::chunk_size(VecOp::InnerProduct, x.len());
+
+        let partials: Vec<[u64; 6]> = x
+            .par_chunks(chunk_size)
+            .zip(y.par_chunks(chunk_size))
+            .map(|(xi, yi)| inner_product(m, xi, yi))
+            .collect();
+        sum(m, &partials)
+    })
+}
+
+/// Elements per broadcast of the constant in the conversions.
+const CONVERT_BLOCK: usize = 1024;
+
+/// `z = x * c` for a single Montgomery-form constant `c`.
+fn mul_by_constant(m: &Modulus384, x: &[[u64; 6]], z: &mut [[u64; 6]], c: [u64; 6]) {
+    let broadcast = vec![c; core::cmp::min(x.len(), CONVERT_BLOCK)];
+    for (xi, zi) in x.chunks(CONVERT_BLOCK).zip(z.chunks_mut(CONVERT_BLOCK)) {
+        mul(m, xi, &broadcast[..xi.len()], zi);
+    }
+}
+
+/// Converts Montgomery-form elements to canonical residues.
+pub fn from_montgomery(m: &Modulus384, x: &[[u64; 6]], z: &mut [[u64; 6]]) {
+    assert_eq!(x.len(), z.len());
+    mul_by_constant(m, x, z, [1, 0, 0, 0, 0, 0]);
+}
+
+/// Converts canonical residues, each below the modulus, to Montgomery form.
+pub fn to_montgomery(m: &Modulus384, x: &[[u64; 6]], z: &mut [[u64; 6]]) {
+    assert_eq!(x.len(), z.len());
+    mul_by_constant(m, x, z, m.r2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const M: &Modulus384 = &Modulus384::BLS12_381_FQ;
+
+    /// Small values in Montgomery form, without any field library.
+    fn small(len: usize, seed: u64) -> Vec<[u64; 6]> {
+        let canonical: Vec<[u64; 6]> = (0..len as u64)
+            .map(|i| [i * seed + 1, 0, 0, 0, 0, 0])
+            .collect();
+        let mut z = vec![[0; 6]; len];
+        to_montgomery(M, &canonical, &mut z);
+        z
+    }
+
+    fn canonical(x: &[[u64; 6]]) -> Vec<[u64; 6]> {
+        let mut z = vec![[0; 6]; x.len()];
+        from_montgomery(M, x, &mut z);
+        z
+    }
+
+    #[test]
+    fn modulus384_constants() {
+        // -p^-1 mod 2^64 and 2^768 mod p for BLS12-381 Fq, as published with the curve.
+        assert_eq!(M.words[6], 0x89f3fffcfffcfffd);
+        assert_eq!(
+            M.r2(),
+            [
+                0xf4df1f341c341746,
+                0x0a76e6a609d104f1,
+                0x8de5476c4c95b6d5,
+                0x67eb88a9939d83c0,
+                0x9a793e85b519952d,
+                0x11988fe592cae3aa,
+            ]
+        );
+
+        #[cfg(feature = "ark")]
+        {
+            use ark_ff::MontConfig;
+            assert_eq!(M.modulus(), ark_bls12_381::FqConfig::MODULUS.0);
+            assert_eq!(M.r2(), ark_bls12_381::FqConfig::R2.0);
+        }
+    }
+
+    #[test]
+    fn limbs384_small_values() {
+        const NUM_OPS: usize = 1_003;
+        let x = small(NUM_OPS, 3);
+        let y = small(NUM_OPS, 5);
+        let mut z = vec![[0; 6]; NUM_OPS];
+
+        mul_par(M, &x, &y, &mut z);
+        add_inplace(M, &mut z, &x);
+        for (i, zi) in canonical(&z).iter().enumerate() {
+            let (xi, yi) = (3 * i as u64 + 1, 5 * i as u64 + 1);
+            assert_eq!(*zi, [xi * yi + xi, 0, 0, 0, 0, 0]);
+        }
+
+        // x - y wraps below zero: p - 2i.
+        sub(M, &x, &y, &mut z);
+        for (i, zi) in canonical(&z).iter().enumerate().skip(1) {
+            let mut expected = M.modulus();
+            expected[0] -= 2 * i as u64;
+            assert_eq!(*zi, expected);
+        }
+
+        let n = NUM_OPS as u64;
+        let sum_x = n * (3 * (n - 1) + 2) / 2;
+        assert_eq!(canonical(&[sum(M, &x)]), [[sum_x, 0, 0, 0, 0, 0]]);
+        assert_eq!(canonical(&[sum_par(M, &x)]), [[sum_x, 0, 0, 0, 0, 0]]);
+
+        let dot: u64 = (0..n).map(|i| (3 * i + 1) * (5 * i + 1)).sum();
+        assert_eq!(
+            canonical(&[inner_product(M, &x, &y)]),
+            [[dot, 0, 0, 0, 0, 0]]
+        );
+        assert_eq!(
+            canonical(&[inner_product_par(M, &x, &y)]),
+            [[dot, 0, 0, 0, 0, 0]]
+        );
+    }
+}
@@ -1,5 +1,34 @@
-use vectorized_fields::benchmarks;
+#[cfg(feature = "parallel")]
+use vectorized_fields::calibrate;
+use vectorized_fields::capi;
 
 fn main() {
-    benchmarks::benchmark();
+    match std::env::args().nth(1).as_deref() {
+        #[cfg(feature = "parallel")]
+        Some("calibrate") => {
+            let calibration = calibrate::calibrate(&calibrate::CalibrationOptions::default());
+            print!("{}", calibration.to_config_string());
+
+            match calibrate::default_config_path() {
+                Some(path) => {
+                    calibration
+                        .save(&path)
+                        .expect("failed to write calibration file");
+                    println!("Saved calibration to {}", path.display());
+                }
+                None => println!("Calibration file disabled; not saved"),
+            }
+        }
+        Some("header") => print!("{}", capi::c_header()),
+        Some(other) => {
+            eprintln!("unknown command {other:?}; expected no arguments, `calibrate` or `header`");
+            std::process::exit(2);
+        }
+        #[cfg(all(feature = "ark", feature = "parallel"))]
+        None => vectorized_fields::benchmarks::benchmark(),
+        #[cfg(not(all(feature = "ark", feature = "parallel")))]
+        None => eprintln!(
+            "the benchmarks compare against arkworks on rayon; build with `ark` and `parallel`"
+        ),
+    }
 }
@@ -0,0 +1,359 @@
+//! Field vectors stored in memory-mapped files, for tables larger than RAM.
+//!
+//! A file holds the raw `LIMBS`-limb Montgomery representation of each element with no header.
+//! Operations walk the mappings in windows of [`MMAP_WINDOW_LEN`] elements and hand each window
+//! back to the kernel once it is done, so the resident set stays around a few windows no matter
+//! how large the files are.
+
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    marker::PhantomData,
+    mem,
+    ops::{Deref, DerefMut, Range},
+    os::fd::AsRawFd,
+    path::Path,
+    ptr::NonNull,
+    slice,
+};
+
+use crate::field::VectorizedField;
+
+/// Default number of elements per window, 32 MiB of 256-bit elements.
+pub const MMAP_WINDOW_LEN: usize = 1 << 20;
+
+/// A vector of field elements backed by a shared, writable mapping of a file.
+pub struct MmapFieldVec<F: VectorizedField> {
+    ptr: NonNull<F>,
+    len: usize,
+    /// Length of the mapping in bytes, `len` elements.
+    size: usize,
+    window_len: usize,
+    _file: File,
+    _marker: PhantomData<F>,
+}
+
+unsafe impl<F: VectorizedField> Send for MmapFieldVec<F> {}
+unsafe impl<F: VectorizedField> Sync for MmapFieldVec<F> {}
+
+impl<F: VectorizedField> MmapFieldVec<F> {
+    /// Size in bytes of `len` elements, an `InvalidInput` error if it does not fit an `isize`.
+    fn byte_size(len: usize) -> io::Result<usize> {
+        len.checked_mul(mem::size_of::<F>())
+            .filter(|&size| size <= isize::MAX as usize)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{len} elements overflow the address space"),
+                )
+            })
+    }
+
+    /// Creates (or truncates) the file at `path` to hold `len` zeros and maps it.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be truncated or modified by anything else while it is mapped.
+    pub unsafe fn create(path: impl AsRef<Path>, len: usize) -> io::Result<Self> {
+        let size = Self::byte_size(len)?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(size as u64)?;
+        Self::map(file, len, size)
+    }
+
+    /// Maps an existing file of elements for reading and writing.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be truncated or modified by anything else while it is mapped.
+    pub unsafe fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let size = usize::try_from(file.metadata()?.len())
+            .ok()
+            .filter(|&size| size <= isize::MAX as usize)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "file too large to map"))?;
+        if !size.is_multiple_of(mem::size_of::<F>()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "file size {size} is not a multiple of the {}-byte element size",
+                    mem::size_of::<F>()
+                ),
+            ));
+        }
+        Self::map(file, size / mem::size_of::<F>(), size)
+    }
+
+    /// Maps `size` bytes of `file`, `len` elements.
+    unsafe fn map(file: File, len: usize, size: usize) -> io::Result<Self> {
+        if len == 0 {
+            return Ok(Self {
+                ptr: NonNull::dangling(),
+                len,
+                size,
+                window_len: MMAP_WINDOW_LEN,
+                _file: file,
+                _marker: PhantomData,
+            });
+        }
+
+        let ptr = libc::mmap(
+            std::ptr::null_mut(),
+            size,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED,
+            file.as_raw_fd(),
+            0,
+        );
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        // Best effort: the kernels read every mapping front to back.
+        libc::madvise(ptr, size, libc::MADV_SEQUENTIAL);
+
+        Ok(Self {
+            ptr: NonNull::new(ptr as *mut F).unwrap(),
+            len,
+            size,
+            window_len: MMAP_WINDOW_LEN,
+            _file: file,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Processes `window_len` elements at a time instead of [`MMAP_WINDOW_LEN`].
+    pub fn with_window_len(mut self, window_len: usize) -> Self {
+        self.window_len = window_len.max(1);
+        self
+    }
+
+    pub fn window_len(&self) -> usize {
+        self.window_len
+    }
+
+    pub fn as_slice(&self) -> &[F] {
+        self
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [F] {
+        self
+    }
+
+    /// Writes dirty pages back to the file and waits for completion.
+    pub fn flush(&self) -> io::Result<()> {
+        if self.len == 0 {
+            return Ok(());
+        }
+        let ret = unsafe {
+            libc::msync(
+                self.ptr.as_ptr() as *mut libc::c_void,
+                self.size,
+                libc::MS_SYNC,
+            )
+        };
+        if ret != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Drops the pages of `range` from this process. Data is kept: clean pages are re-read
+    /// from the file and dirty ones stay in the page cache until written back.
+    fn release(&self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let base = self.ptr.as_ptr() as usize;
+        let start = (base + range.start * mem::size_of::<F>()).next_multiple_of(page);
+        let end = (base + range.end * mem::size_of::<F>()) / page * page;
+        if start < end {
+            unsafe { libc::madvise(start as *mut libc::c_void, end - start, libc::MADV_DONTNEED) };
+        }
+    }
+
+    fn windows(&self) -> impl Iterator<Item = Range<usize>> {
+        let (len, window_len) = (self.len, self.window_len);
+        (0..len)
+            .step_by(window_len)
+            .map(move |start| start..std::cmp::min(start + window_len, len))
+    }
+
+    /// Runs `op(x[w], y[w], z[w])` window by window.
+    fn zip_into(&self, y: &Self, z: &mut Self, op: fn(&[F], &[F], &mut [F])) {
+        assert_eq!(self.len, y.len);
+        assert_eq!(self.len, z.len);
+
+        for w in self.windows() {
+            op(&self[w.clone()], &y[w.clone()], &mut z[w.clone()]);
+            self.release(w.clone());
+            y.release(w.clone());
+            z.release(w);
+        }
+    }
+
+    /// Sums `reduce(x[w], y[w])` over the windows.
+    fn reduce(&self, y: Option<&Self>, reduce: impl Fn(Range<usize>) -> F) -> F {
+        if let Some(y) = y {
+            assert_eq!(self.len, y.len);
+        }
+
+        let partials: Vec<F> = self
+            .windows()
+            .map(|w| {
+                let partial = reduce(w.clone());
+                self.release(w.clone());
+                if let Some(y) = y {
+                    y.release(w);
+                }
+                partial
+            })
+            .collect();
+        F::sum_vec(&partials)
+    }
+
+    /// `z = self + y`
+    pub fn add_into(&self, y: &Self, z: &mut Self) {
+        self.zip_into(y, z, F::add_vec)
+    }
+
+    /// `z = self + y`, each window split across rayon.
+    pub fn par_add_into(&self, y: &Self, z: &mut Self) {
+        self.zip_into(y, z, F::add_vec_par)
+    }
+
+    /// `z = self - y`
+    pub fn sub_into(&self, y: &Self, z: &mut Self) {
+        self.zip_into(y, z, F::sub_vec)
+    }
+
+    pub fn par_sub_into(&self, y: &Self, z: &mut Self) {
+        self.zip_into(y, z, F::sub_vec_par)
+    }
+
+    /// `z = self * y`, elementwise.
+    pub fn mul_into(&self, y: &Self, z: &mut Self) {
+        self.zip_into(y, z, F::mul_vec)
+    }
+
+    pub fn par_mul_into(&self, y: &Self, z: &mut Self) {
+        self.zip_into(y, z, F::mul_vec_par)
+    }
+
+    pub fn sum(&self) -> F {
+        self.reduce(None, |w| F::sum_vec(&self[w]))
+    }
+
+    pub fn par_sum(&self) -> F {
+        self.reduce(None, |w| F::sum_vec_par(&self[w]))
+    }
+
+    pub fn dot(&self, y: &Self) -> F {
+        self.reduce(Some(y), |w| F::inner_product(&self[w.clone()], &y[w]))
+    }
+
+    pub fn par_dot(&self, y: &Self) -> F {
+        self.reduce(Some(y), |w| F::inner_product_par(&self[w.clone()], &y[w]))
+    }
+}
+
+impl<F: VectorizedField> Drop for MmapFieldVec<F> {
+    fn drop(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+        unsafe { libc::munmap(self.ptr.as_ptr() as *mut libc::c_void, self.size) };
+    }
+}
+
+impl<F: VectorizedField> Deref for MmapFieldVec<F> {
+    type Target = [F];
+
+    fn deref(&self) -> &[F] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<F: VectorizedField> DerefMut for MmapFieldVec<F> {
+    fn deref_mut(&mut self) -> &mut [F] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+#[cfg(all(test, feature = "ark"))]
+mod tests {
+    use super::*;
+    use crate::ark::*;
+    use crate::utils::rand_vec;
+    use ark_bn254::Fr;
+    use ark_std::Zero;
+
+    #[test]
+    fn parity_ark_mmap_field_vec() {
+        const NUM_OPS: usize = 1_003;
+        let x: Vec<Fr> = rand_vec(NUM_OPS);
+        let y: Vec<Fr> = rand_vec(NUM_OPS);
+        let mut ark_z = vec![Fr::zero(); NUM_OPS];
+
+        let dir = std::env::temp_dir();
+        let path = |name: &str| {
+            dir.join(format!(
+                "vectorized-fields-mmap-{name}-{}.bin",
+                std::process::id()
+            ))
+        };
+
+        unsafe {
+            let mut mx = MmapFieldVec::<Fr>::create(path("x"), NUM_OPS).unwrap();
+            let mut my = MmapFieldVec::<Fr>::create(path("y"), NUM_OPS).unwrap();
+            mx.copy_from_slice(&x);
+            my.copy_from_slice(&y);
+            mx.flush().unwrap();
+
+            // Reopen to check the data round-trips through the file, with several windows.
+            drop(mx);
+            let mx = MmapFieldVec::<Fr>::open(path("x"))
+                .unwrap()
+                .with_window_len(100);
+            let mut mz = MmapFieldVec::<Fr>::create(path("z"), NUM_OPS).unwrap();
+            assert_eq!(mx.as_slice(), x);
+
+            ark_batch_add(&x, &y, &mut ark_z);
+            mx.add_into(&my, &mut mz);
+            assert_eq!(mz.as_slice(), ark_z);
+
+            ark_batch_sub(&x, &y, &mut ark_z);
+            mx.par_sub_into(&my, &mut mz);
+            assert_eq!(mz.as_slice(), ark_z);
+
+            ark_batch_mul(&x, &y, &mut ark_z);
+            mx.mul_into(&my, &mut mz);
+            assert_eq!(mz.as_slice(), ark_z);
+
+            assert_eq!(mx.sum(), ark_batch_sum(&x));
+            assert_eq!(mx.par_sum(), ark_batch_sum(&x));
+            assert_eq!(mx.dot(&my), ark_inner_product(&x, &y));
+            assert_eq!(mx.par_dot(&my), ark_inner_product(&x, &y));
+        }
+
+        for name in ["x", "y", "z"] {
+            std::fs::remove_file(path(name)).unwrap();
+        }
+    }
+
+    #[test]
+    fn mmap_field_vec_size_overflow() {
+        let path = std::env::temp_dir().join(format!(
+            "vectorized-fields-mmap-overflow-{}.bin",
+            std::process::id()
+        ));
+        let err = unsafe { MmapFieldVec::<Fr>::create(&path, usize::MAX / 16) }.err();
+        assert_eq!(err.map(|e| e.kind()), Some(io::ErrorKind::InvalidInput));
+        assert!(!path.exists());
+    }
+}
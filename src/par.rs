@@ -0,0 +1,87 @@
+//! The rayon API used by the `_par` functions.
+//!
+//! With the `parallel` feature this is rayon itself. Without it, the same methods are provided
+//! on the core slice and iterator types and run on the calling thread, so every `_par` function
+//! keeps its signature and its result, and the scheduler sees a single-thread pool.
+
+#[cfg(feature = "parallel")]
+pub(crate) use rayon::{current_num_threads, prelude::*};
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) use serial::*;
+
+// Not every build uses every adaptor, e.g. `par_iter_mut` only backs the arkworks baselines.
+#[cfg(not(feature = "parallel"))]
+#[allow(dead_code)]
+mod serial {
+    use core::slice::{Chunks, ChunksMut, Iter, IterMut};
+
+    pub(crate) fn current_num_threads() -> usize {
+        1
+    }
+
+    pub(crate) trait ParallelSlice<T> {
+        fn par_chunks(&self, chunk_size: usize) -> Chunks<'_, T>;
+        fn par_iter(&self) -> Iter<'_, T>;
+    }
+
+    impl<T> ParallelSlice<T> for [T] {
+        fn par_chunks(&self, chunk_size: usize) -> Chunks<'_, T> {
+            self.chunks(chunk_size)
+        }
+
+        fn par_iter(&self) -> Iter<'_, T> {
+            self.iter()
+        }
+    }
+
+    pub(crate) trait ParallelSliceMut<T> {
+        fn par_chunks_mut(&mut self, chunk_size: usize) -> ChunksMut<'_, T>;
+        fn par_iter_mut(&mut self) -> IterMut<'_, T>;
+    }
+
+    impl<T> ParallelSliceMut<T> for [T] {
+        fn par_chunks_mut(&mut self, chunk_size: usize) -> ChunksMut<'_, T> {
+            self.chunks_mut(chunk_size)
+        }
+
+        fn par_iter_mut(&mut self) -> IterMut<'_, T> {
+            self.iter_mut()
+        }
+    }
+
+    pub(crate) trait IntoParallelIterator: IntoIterator + Sized {
+        fn into_par_iter(self) -> Self::IntoIter {
+            self.into_iter()
+        }
+    }
+
+    impl<I: IntoIterator> IntoParallelIterator for I {}
+
+    /// The rayon adaptors that have no `Iterator` method of the same name.
+    pub(crate) trait ParallelIterator: Iterator + Sized {
+        fn for_each_init<T, INIT, OP>(self, init: INIT, mut op: OP)
+        where
+            INIT: Fn() -> T,
+            OP: FnMut(&mut T, Self::Item),
+        {
+            let mut state = init();
+            self.for_each(|item| op(&mut state, item));
+        }
+
+        fn map_init<T, INIT, R, OP>(self, init: INIT, mut op: OP) -> impl Iterator<Item = R>
+        where
+            INIT: Fn() -> T,
+            OP: FnMut(&mut T, Self::Item) -> R,
+        {
+            let mut state = init();
+            self.map(move |item| op(&mut state, item))
+        }
+
+        fn find_map_first<R>(mut self, f: impl FnMut(Self::Item) -> Option<R>) -> Option<R> {
+            self.find_map(f)
+        }
+    }
+
+    impl<I: Iterator> ParallelIterator for I {}
+}
@@ -0,0 +1,267 @@
+use ark_bn254::Fr;
+use ark_ff::Field;
+use ark_std::{One, Zero};
+
+use crate::{
+    add_vec_inplace_bn254, inner_product_bn254,
+    limbs::MUL_BLOCK,
+    mul_vec_inplace_bn254,
+    par::*,
+    schedule::{self, VecOp},
+};
+
+/// Lanes of the Horner evaluations, one iteration of the AVX-512 loop of the mul kernel. Fewer
+/// elements per call would all go through its scalar prologue.
+const LANES: usize = MUL_BLOCK;
+
+/// Points evaluated together by [`poly_eval_many_bn254`], four iterations of that loop per call.
+const POINTS_PER_CHUNK: usize = 4 * MUL_BLOCK;
+
+/// Evaluates `p(z) = Σ_i coeffs[i] · z^i`.
+///
+/// The coefficients are split into 16 interleaved lanes `P_l(w) = Σ_j coeffs[16j + l] · w^j`,
+/// which run Horner's rule in `w = z^16` side by side, one vector multiply per step, and are
+/// recombined as `p(z) = Σ_l z^l · P_l(z^16)`.
+pub fn poly_eval_bn254(coeffs: &[Fr], z: Fr) -> Fr {
+    let mut z_powers = [Fr::one(); LANES];
+    for l in 1..LANES {
+        z_powers[l] = z_powers[l - 1] * z;
+    }
+    let w = [z_powers[LANES - 1] * z; LANES];
+
+    let mut acc = [Fr::zero(); LANES];
+    for block in coeffs.chunks(LANES).rev() {
+        mul_vec_inplace_bn254(&mut acc, &w);
+        if block.len() == LANES {
+            add_vec_inplace_bn254(&mut acc, block);
+        } else {
+            let mut padded = [Fr::zero(); LANES];
+            padded[..block.len()].copy_from_slice(block);
+            add_vec_inplace_bn254(&mut acc, &padded);
+        }
+    }
+
+    inner_product_bn254(&acc, &z_powers)
+}
+
+/// Evaluates blocks of coefficients independently and shifts each block by `z^start`.
+pub fn poly_eval_par_bn254(coeffs: &[Fr], z: Fr) -> Fr {
+    schedule::install(VecOp::PolyEval, || {
+        let chunk_size = schedule::chunk_size(VecOp::PolyEval, coeffs.len());
+
+        coeffs
+            .par_chunks(chunk_size)
+            .enumerate()
+            .map(|(i, chunk)| poly_eval_bn254(chunk, z) * z.pow([(i * chunk_size) as u64]))
+            .sum::<Fr>()
+    })
+}
+
+/// Evaluates `p` at every point, running Horner's rule on up to 64 points at a time, one vector
+/// multiply per coefficient. A short last chunk is padded to a multiple of 16 points.
+pub fn poly_eval_many_bn254(coeffs: &[Fr], points: &[Fr]) -> Vec<Fr> {
+    let mut evals = Vec::with_capacity(points.len());
+
+    for chunk in points.chunks(POINTS_PER_CHUNK) {
+        let padded_len = chunk.len().next_multiple_of(LANES);
+        let mut x = [Fr::zero(); POINTS_PER_CHUNK];
+        x[..chunk.len()].copy_from_slice(chunk);
+        let x = &x[..padded_len];
+
+        let mut acc = [Fr::zero(); POINTS_PER_CHUNK];
+        let acc = &mut acc[..padded_len];
+        for c in coeffs.iter().rev() {
+            mul_vec_inplace_bn254(acc, x);
+            add_vec_inplace_bn254(acc, &[*c; POINTS_PER_CHUNK][..padded_len]);
+        }
+        evals.extend_from_slice(&acc[..chunk.len()]);
+    }
+
+    evals
+}
+
+/// Runs the synthetic division recurrence `t[i] = coeffs[i] + a · t[i + 1]` in place,
+/// starting from `t[len] = 0`.
+fn synthetic_division_inplace(t: &mut [Fr], a: Fr) {
+    let mut acc = Fr::zero();
+    for ti in t.iter_mut().rev() {
+        acc = *ti + a * acc;
+        *ti = acc;
+    }
+}
+
+/// Splits `t = coeffs` into `(quotient, remainder)` once the recurrence has run.
+fn split_quotient(mut t: Vec<Fr>) -> (Vec<Fr>, Fr) {
+    if t.is_empty() {
+        return (t, Fr::zero());
+    }
+    let remainder = t.remove(0);
+    (t, remainder)
+}
+
+/// Divides `p(X) = Σ_i coeffs[i] · X^i` by `(X - a)`, returning the quotient
+/// coefficients and the remainder `p(a)`.
+pub fn divide_by_linear_bn254(coeffs: &[Fr], a: Fr) -> (Vec<Fr>, Fr) {
+    let mut t = coeffs.to_vec();
+    synthetic_division_inplace(&mut t, a);
+    split_quotient(t)
+}
+
+/// Block formulation of [`divide_by_linear_bn254`].
+///
+/// Within a block `[s, e)` the recurrence satisfies `t[i] = t_local[i] + a^(e - i) · t[e]`,
+/// so every block first runs the recurrence locally from zero, the block carries `t[e]`
+/// are chained from the top, and the correction is applied with the vector kernels.
+pub fn divide_by_linear_par_bn254(coeffs: &[Fr], a: Fr) -> (Vec<Fr>, Fr) {
+    schedule::install(VecOp::DivideByLinear, || {
+        let chunk_size = schedule::chunk_size(VecOp::DivideByLinear, coeffs.len());
+        let num_chunks = coeffs.len().div_ceil(chunk_size);
+        if num_chunks <= 1 {
+            return divide_by_linear_bn254(coeffs, a);
+        }
+
+        let mut t = coeffs.to_vec();
+        t.par_chunks_mut(chunk_size)
+            .for_each(|chunk| synthetic_division_inplace(chunk, a));
+
+        // powers[k] = a^(chunk_size - k); a block of length `len` uses
+        // `powers[chunk_size - len..]`.
+        let mut powers = vec![a; chunk_size];
+        for k in (0..chunk_size - 1).rev() {
+            powers[k] = powers[k + 1] * a;
+        }
+
+        // carries[b] = t[e] for block b, i.e. the fully corrected first entry of block b + 1.
+        let mut carries = vec![Fr::zero(); num_chunks];
+        for b in (0..num_chunks - 1).rev() {
+            let next_start = (b + 1) * chunk_size;
+            let next_len = std::cmp::min(chunk_size, t.len() - next_start);
+            carries[b] = t[next_start] + powers[chunk_size - next_len] * carries[b + 1];
+        }
+
+        t.par_chunks_mut(chunk_size)
+            .zip(carries.par_iter())
+            .for_each_init(Vec::new, |correction, (chunk, carry)| {
+                if carry.is_zero() {
+                    return;
+                }
+                correction.clear();
+                correction.resize(chunk.len(), *carry);
+                mul_vec_inplace_bn254(correction, &powers[chunk_size - chunk.len()..]);
+                add_vec_inplace_bn254(chunk, correction);
+            });
+
+        split_quotient(t)
+    })
+}
+
+/// Divides every polynomial by the same `(X - a)`, one polynomial per rayon task.
+pub fn divide_many_by_linear_bn254(polys: &[&[Fr]], a: Fr) -> Vec<(Vec<Fr>, Fr)> {
+    polys
+        .par_iter()
+        .map(|coeffs| divide_by_linear_bn254(coeffs, a))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ark::ark_horner;
+    use crate::utils::rand_vec;
+
+    #[test]
+    fn parity_ark_poly_eval() {
+        for num_coeffs in [0, 1, 15, 16, 17, 1_000] {
+            let coeffs: Vec<Fr> = rand_vec(num_coeffs);
+            let z: Fr = rand_vec(1)[0];
+
+            assert_eq!(poly_eval_bn254(&coeffs, z), ark_horner(&coeffs, z));
+        }
+    }
+
+    #[test]
+    fn parallel_parity_poly_eval() {
+        const NUM_COEFFS: usize = 10_000;
+        let coeffs: Vec<Fr> = rand_vec(NUM_COEFFS);
+        let z: Fr = rand_vec(1)[0];
+
+        assert_eq!(poly_eval_bn254(&coeffs, z), poly_eval_par_bn254(&coeffs, z));
+    }
+
+    #[test]
+    fn parity_ark_poly_eval_many() {
+        const NUM_COEFFS: usize = 100;
+        let coeffs: Vec<Fr> = rand_vec(NUM_COEFFS);
+
+        for num_points in [0, 1, 37, 64, 150] {
+            let points: Vec<Fr> = rand_vec(num_points);
+            let ark_evals: Vec<Fr> = points.iter().map(|&z| ark_horner(&coeffs, z)).collect();
+            assert_eq!(poly_eval_many_bn254(&coeffs, &points), ark_evals);
+        }
+    }
+
+    fn ark_divide_by_linear(coeffs: &[Fr], a: Fr) -> (Vec<Fr>, Fr) {
+        let mut quotient = vec![Fr::zero(); coeffs.len().saturating_sub(1)];
+        let mut acc = Fr::zero();
+        for i in (0..coeffs.len()).rev() {
+            acc = coeffs[i] + a * acc;
+            if i > 0 {
+                quotient[i - 1] = acc;
+            }
+        }
+        (quotient, acc)
+    }
+
+    #[test]
+    fn parity_ark_divide_by_linear() {
+        for num_coeffs in [0, 1, 2, 1_000] {
+            let coeffs: Vec<Fr> = rand_vec(num_coeffs);
+            let a: Fr = rand_vec(1)[0];
+
+            let (quotient, remainder) = divide_by_linear_bn254(&coeffs, a);
+            assert_eq!(
+                (quotient.clone(), remainder),
+                ark_divide_by_linear(&coeffs, a)
+            );
+            assert_eq!(remainder, ark_horner(&coeffs, a));
+
+            // p(z) = q(z) · (z - a) + p(a)
+            let z: Fr = rand_vec(1)[0];
+            assert_eq!(
+                ark_horner(&coeffs, z),
+                ark_horner(&quotient, z) * (z - a) + remainder
+            );
+        }
+    }
+
+    #[test]
+    fn parallel_parity_divide_by_linear() {
+        for num_coeffs in [0, 1, 1_024, 10_000] {
+            let coeffs: Vec<Fr> = rand_vec(num_coeffs);
+            let a: Fr = rand_vec(1)[0];
+
+            assert_eq!(
+                divide_by_linear_bn254(&coeffs, a),
+                divide_by_linear_par_bn254(&coeffs, a)
+            );
+            // Blocks of 100 split every length past 100, the last block short.
+            #[cfg(feature = "parallel")]
+            assert_eq!(
+                divide_by_linear_bn254(&coeffs, a),
+                schedule::with_chunk_size(Some(100), || divide_by_linear_par_bn254(&coeffs, a))
+            );
+        }
+    }
+
+    #[test]
+    fn parity_divide_many_by_linear() {
+        let polys: Vec<Vec<Fr>> = (0..5).map(|i| rand_vec(100 * i + 3)).collect();
+        let poly_refs: Vec<&[Fr]> = polys.iter().map(Vec::as_slice).collect();
+        let a: Fr = rand_vec(1)[0];
+
+        let batched = divide_many_by_linear_bn254(&poly_refs, a);
+        for (coeffs, result) in polys.iter().zip(batched) {
+            assert_eq!(result, divide_by_linear_bn254(coeffs, a));
+        }
+    }
+}
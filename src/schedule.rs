@@ -0,0 +1,405 @@
+//! Chooses how the `_par` functions split their work across rayon.
+//!
+//! Each operation has an estimated per-element cost. Chunks are sized so that every rayon task
+//! carries at least [`MIN_TASK_NS`] of work, while still producing [`TASKS_PER_THREAD`] tasks per
+//! thread on long vectors for load balancing. Both choices, and the pool an operation runs on,
+//! can be overridden per operation, and a per-host calibration file written by
+//! `crate::calibrate` is applied on first use.
+//! Without the `parallel` feature the pool is the calling thread, so nothing is split.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "parallel")]
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    sync::{Arc, Mutex, Once},
+};
+
+/// Minimum estimated work per rayon task, in nanoseconds.
+pub const MIN_TASK_NS: usize = 20_000;
+
+/// Number of tasks per thread to aim for on long vectors.
+pub const TASKS_PER_THREAD: usize = 4;
+
+/// Chunks of the operations without a blocked kernel are rounded to 8 elements, whole cache lines.
+const CHUNK_ALIGN: usize = 8;
+
+/// Output size, in bytes, from which the `_auto` functions write with non-temporal stores. Well
+/// above the last-level cache of current server parts, so streamed results would not have stayed
+/// cached anyway.
+pub const STREAMING_MIN_BYTES: usize = 256 << 20;
+
+/// Marks an override slot as unset.
+const UNSET: usize = usize::MAX;
+
+/// Operations with their own scheduling parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VecOp {
+    Add,
+    Sub,
+    Mul,
+    Sum,
+    InnerProduct,
+    LinearCombination,
+    SumVectors,
+    PolyEval,
+    DivideByLinear,
+    Spmv,
+    /// The `< p` range check of `validate_canonical` and of the checked conversions.
+    Validate,
+}
+
+impl VecOp {
+    pub const ALL: [VecOp; 11] = [
+        VecOp::Add,
+        VecOp::Sub,
+        VecOp::Mul,
+        VecOp::Sum,
+        VecOp::InnerProduct,
+        VecOp::LinearCombination,
+        VecOp::SumVectors,
+        VecOp::PolyEval,
+        VecOp::DivideByLinear,
+        VecOp::Spmv,
+        VecOp::Validate,
+    ];
+
+    /// Estimated single-threaded cost of one element, in nanoseconds.
+    ///
+    /// Add, sub, mul and inner product follow the single-threaded AVX-512 rows of
+    /// `benchmarks/c7a.8xlarge.md`. For the multi-vector operations the cost is per output
+    /// element and per input vector; for `Spmv` it is per row.
+    pub fn cost_ns(self) -> usize {
+        match self {
+            VecOp::Add | VecOp::Sub => 3,
+            VecOp::Mul => 10,
+            VecOp::Sum => 1,
+            VecOp::InnerProduct => 7,
+            VecOp::LinearCombination => 8,
+            VecOp::SumVectors => 2,
+            VecOp::PolyEval => 4,
+            VecOp::DivideByLinear => 15,
+            VecOp::Spmv => 50,
+            VecOp::Validate => 1,
+        }
+    }
+
+    /// Multiple that chunks are rounded to. The operations built on the 256-bit mul kernel take it
+    /// in blocks of [`MUL_BLOCK`](crate::limbs::MUL_BLOCK), so only the last chunk runs the
+    /// scalar remainder.
+    fn chunk_align(self) -> usize {
+        match self {
+            VecOp::Mul | VecOp::InnerProduct | VecOp::PolyEval | VecOp::DivideByLinear => {
+                crate::limbs::MUL_BLOCK
+            }
+            _ => CHUNK_ALIGN,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            VecOp::Add => "add",
+            VecOp::Sub => "sub",
+            VecOp::Mul => "mul",
+            VecOp::Sum => "sum",
+            VecOp::InnerProduct => "inner_product",
+            VecOp::LinearCombination => "linear_combination",
+            VecOp::SumVectors => "sum_vectors",
+            VecOp::PolyEval => "poly_eval",
+            VecOp::DivideByLinear => "divide_by_linear",
+            VecOp::Spmv => "spmv",
+            VecOp::Validate => "validate",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<VecOp> {
+        VecOp::ALL.into_iter().find(|op| op.name() == name)
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+const NUM_OPS: usize = VecOp::ALL.len();
+
+static CHUNK_SIZE_OVERRIDES: [AtomicUsize; NUM_OPS] = [const { AtomicUsize::new(UNSET) }; NUM_OPS];
+static MIN_PARALLEL_LEN_OVERRIDES: [AtomicUsize; NUM_OPS] =
+    [const { AtomicUsize::new(UNSET) }; NUM_OPS];
+static MAX_THREADS_OVERRIDES: [AtomicUsize; NUM_OPS] = [const { AtomicUsize::new(UNSET) }; NUM_OPS];
+static NUM_THREADS_OVERRIDES: [AtomicUsize; NUM_OPS] = [const { AtomicUsize::new(UNSET) }; NUM_OPS];
+
+static STREAMING_MIN_BYTES_OVERRIDE: AtomicUsize = AtomicUsize::new(UNSET);
+
+#[cfg(feature = "parallel")]
+static LOAD_CALIBRATION: Once = Once::new();
+
+#[cfg(feature = "parallel")]
+thread_local! {
+    /// Chunk size of the `ParContext` currently running on this thread: `None` outside any
+    /// context, `Some(None)` in a context without its own chunk size.
+    static SCOPED_CHUNK_SIZE: Cell<Option<Option<usize>>> = const { Cell::new(None) };
+}
+
+/// Pools backing [`set_num_threads`], one per thread count, built on first use and kept.
+#[cfg(feature = "parallel")]
+static POOLS: Mutex<Option<HashMap<usize, Arc<rayon::ThreadPool>>>> = Mutex::new(None);
+
+/// Applies the host calibration file, if any, exactly once and before any explicit override.
+/// Serial builds have nothing to calibrate.
+fn ensure_calibrated() {
+    #[cfg(feature = "parallel")]
+    LOAD_CALIBRATION.call_once(crate::calibrate::load_host_calibration);
+}
+
+fn load(slot: &AtomicUsize) -> Option<usize> {
+    match slot.load(Ordering::Relaxed) {
+        UNSET => None,
+        value => Some(value),
+    }
+}
+
+fn store(slot: &AtomicUsize, value: Option<usize>) {
+    slot.store(value.unwrap_or(UNSET), Ordering::Relaxed);
+}
+
+/// Forces the chunk size used by `op`, or restores the cost model with `None`.
+pub fn set_chunk_size(op: VecOp, chunk_size: Option<usize>) {
+    ensure_calibrated();
+    store(
+        &CHUNK_SIZE_OVERRIDES[op.index()],
+        chunk_size.map(|c| c.max(1)),
+    );
+}
+
+/// Forces the length from which the `_auto` functions run `op` in parallel, or restores the
+/// cost model with `None`.
+pub fn set_min_parallel_len(op: VecOp, min_len: Option<usize>) {
+    ensure_calibrated();
+    store(&MIN_PARALLEL_LEN_OVERRIDES[op.index()], min_len);
+}
+
+/// Caps the number of chunks, and so the number of busy threads, used by `op`, or removes
+/// the cap with `None`. Memory-bound kernels stop scaling well before all cores are busy.
+pub fn set_max_threads(op: VecOp, max_threads: Option<usize>) {
+    ensure_calibrated();
+    store(
+        &MAX_THREADS_OVERRIDES[op.index()],
+        max_threads.map(|t| t.max(1)),
+    );
+}
+
+/// Runs `op` on a dedicated pool of `num_threads` threads instead of the current one, or
+/// restores the current pool with `None`. Calibrated thread counts are applied this way.
+pub fn set_num_threads(op: VecOp, num_threads: Option<usize>) {
+    ensure_calibrated();
+    store(
+        &NUM_THREADS_OVERRIDES[op.index()],
+        num_threads.map(|t| t.max(1)),
+    );
+}
+
+pub fn chunk_size_override(op: VecOp) -> Option<usize> {
+    ensure_calibrated();
+    load(&CHUNK_SIZE_OVERRIDES[op.index()])
+}
+
+pub fn min_parallel_len_override(op: VecOp) -> Option<usize> {
+    ensure_calibrated();
+    load(&MIN_PARALLEL_LEN_OVERRIDES[op.index()])
+}
+
+/// Forces the output size, in bytes, from which the `_auto` functions use non-temporal stores,
+/// or restores [`STREAMING_MIN_BYTES`] with `None`.
+pub fn set_streaming_min_bytes(min_bytes: Option<usize>) {
+    store(&STREAMING_MIN_BYTES_OVERRIDE, min_bytes);
+}
+
+pub fn streaming_min_bytes_override() -> Option<usize> {
+    load(&STREAMING_MIN_BYTES_OVERRIDE)
+}
+
+/// Stores calibrated settings without triggering the calibration load, which calls this.
+#[cfg(feature = "parallel")]
+pub(crate) fn apply_calibrated(op: VecOp, chunk_size: usize, num_threads: usize) {
+    store(&CHUNK_SIZE_OVERRIDES[op.index()], Some(chunk_size.max(1)));
+    store(&NUM_THREADS_OVERRIDES[op.index()], Some(num_threads.max(1)));
+}
+
+/// Serializes tests that change the global overrides.
+#[cfg(test)]
+pub(crate) static OVERRIDES_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+pub fn max_threads_override(op: VecOp) -> Option<usize> {
+    ensure_calibrated();
+    load(&MAX_THREADS_OVERRIDES[op.index()])
+}
+
+pub fn num_threads_override(op: VecOp) -> Option<usize> {
+    ensure_calibrated();
+    load(&NUM_THREADS_OVERRIDES[op.index()])
+}
+
+/// Drops every override, including those loaded from the calibration file.
+pub fn reset_overrides() {
+    for op in VecOp::ALL {
+        set_chunk_size(op, None);
+        set_min_parallel_len(op, None);
+        set_max_threads(op, None);
+        set_num_threads(op, None);
+    }
+    set_streaming_min_bytes(None);
+}
+
+/// Chunk size for running `op` over `len` elements on the current rayon pool.
+pub fn chunk_size(op: VecOp, len: usize) -> usize {
+    chunk_size_scaled(op, len, 1)
+}
+
+/// Whether `op` over `len` elements is worth splitting across the current rayon pool.
+pub fn should_parallelize(op: VecOp, len: usize) -> bool {
+    should_parallelize_scaled(op, len, 1)
+}
+
+/// Runs `f` with `chunk_size` taking precedence over every override for the `_par` functions
+/// called from this thread, and with those functions kept on the calling pool.
+#[cfg(all(feature = "ark", feature = "parallel"))]
+pub(crate) fn with_chunk_size<R>(chunk_size: Option<usize>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Option<usize>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPED_CHUNK_SIZE.set(self.0);
+        }
+    }
+
+    let _restore = Restore(SCOPED_CHUNK_SIZE.replace(Some(chunk_size)));
+    f()
+}
+
+/// Runs `f`, the parallel part of a `_par` function for `op`, on the pool set with
+/// [`set_num_threads`], if any. Inside a `ParContext` the context's pool is kept.
+#[cfg(feature = "parallel")]
+pub(crate) fn install<R: Send>(op: VecOp, f: impl FnOnce() -> R + Send) -> R {
+    match num_threads_override(op) {
+        Some(num_threads)
+            if SCOPED_CHUNK_SIZE.get().is_none() && num_threads != rayon::current_num_threads() =>
+        {
+            pool(num_threads).install(f)
+        }
+        _ => f(),
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn install<R>(_op: VecOp, f: impl FnOnce() -> R) -> R {
+    f()
+}
+
+#[cfg(feature = "parallel")]
+fn pool(num_threads: usize) -> Arc<rayon::ThreadPool> {
+    let mut pools = POOLS.lock().unwrap();
+    let pool = pools
+        .get_or_insert_with(HashMap::new)
+        .entry(num_threads)
+        .or_insert_with(|| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .expect("failed to build thread pool");
+            Arc::new(pool)
+        });
+    Arc::clone(pool)
+}
+
+/// [`chunk_size`] for operations whose per-element cost grows with `scale`, such as the number
+/// of vectors in a linear combination.
+pub(crate) fn chunk_size_scaled(op: VecOp, len: usize, scale: usize) -> usize {
+    #[cfg(feature = "parallel")]
+    if let Some(Some(chunk_size)) = SCOPED_CHUNK_SIZE.get() {
+        return chunk_size.min(len).max(1);
+    }
+
+    let chunk_size = chunk_size_override(op).unwrap_or_else(|| {
+        let cost = op.cost_ns() * scale.max(1);
+        let min_chunk = MIN_TASK_NS.div_ceil(cost);
+        let balanced = len.div_ceil(crate::par::current_num_threads() * TASKS_PER_THREAD);
+        core::cmp::max(min_chunk, balanced).next_multiple_of(op.chunk_align())
+    });
+
+    let chunk_size = match max_threads_override(op) {
+        Some(max_threads) => core::cmp::max(chunk_size, len.div_ceil(max_threads)),
+        None => chunk_size,
+    };
+
+    chunk_size.min(len).max(1)
+}
+
+/// Whether an output of `bytes` bytes should be written with non-temporal stores.
+pub fn should_stream(bytes: usize) -> bool {
+    bytes >= streaming_min_bytes_override().unwrap_or(STREAMING_MIN_BYTES)
+}
+
+pub(crate) fn should_parallelize_scaled(op: VecOp, len: usize, scale: usize) -> bool {
+    if let Some(min_len) = min_parallel_len_override(op) {
+        return len >= min_len;
+    }
+
+    crate::par::current_num_threads() > 1 && len * op.cost_ns() * scale.max(1) >= 2 * MIN_TASK_NS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunk_size_bounds_and_overrides() {
+        let _guard = OVERRIDES_LOCK.lock().unwrap();
+        reset_overrides();
+
+        for op in VecOp::ALL {
+            assert_eq!(chunk_size(op, 0), 1);
+            assert_eq!(chunk_size(op, 5), 5);
+
+            let chunk = chunk_size(op, 1 << 20);
+            assert_eq!(chunk % op.chunk_align(), 0);
+            assert!(chunk * op.cost_ns() >= MIN_TASK_NS);
+            assert!(!should_parallelize(op, 1));
+        }
+
+        set_chunk_size(VecOp::Mul, Some(100));
+        set_min_parallel_len(VecOp::Mul, Some(0));
+        assert_eq!(chunk_size(VecOp::Mul, 1 << 20), 100);
+        assert!(should_parallelize(VecOp::Mul, 1));
+
+        set_max_threads(VecOp::Mul, Some(4));
+        assert_eq!(chunk_size(VecOp::Mul, 1 << 20), 1 << 18);
+
+        // Calibrated settings are a chunk size taken as is and a pool of their own.
+        #[cfg(feature = "parallel")]
+        {
+            set_max_threads(VecOp::Mul, None);
+            apply_calibrated(VecOp::Mul, 1000, 3);
+            assert_eq!(chunk_size(VecOp::Mul, 1 << 20), 1000);
+            assert_eq!(install(VecOp::Mul, rayon::current_num_threads), 3);
+            assert_eq!(
+                install(VecOp::Add, rayon::current_num_threads),
+                rayon::current_num_threads()
+            );
+        }
+
+        assert!(!should_stream(STREAMING_MIN_BYTES - 1));
+        set_streaming_min_bytes(Some(0));
+        assert!(should_stream(0));
+
+        reset_overrides();
+        assert_eq!(chunk_size_override(VecOp::Mul), None);
+        assert_eq!(min_parallel_len_override(VecOp::Mul), None);
+        assert_eq!(max_threads_override(VecOp::Mul), None);
+        assert_eq!(num_threads_override(VecOp::Mul), None);
+        assert_eq!(streaming_min_bytes_override(), None);
+
+        for op in VecOp::ALL {
+            assert_eq!(VecOp::from_name(op.name()), Some(op));
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Bulk `ark_serialize` encoding of `Fr` vectors.
+//!
+//! The bytes match `CanonicalSerialize` for `[Fr]` / `Vec<Fr>` exactly: a little-endian `u64`
+//! length, then every element as 32 canonical little-endian bytes (compressed and uncompressed
+//! coincide for field elements). Instead of converting and checking one element at a time, whole
+//! chunks go through the Montgomery conversion and range-check kernels, in parallel when large.
+
+use std::io::{self, Read, Write};
+
+use ark_bn254::Fr;
+use ark_ff::Zero;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+
+use crate::{
+    canonical_to_montgomery_bn254, canonical_to_montgomery_par_bn254, constants,
+    montgomery_to_canonical_bn254, montgomery_to_canonical_par_bn254,
+    schedule::{self, VecOp},
+    utils::{limb_bytes, limb_bytes_mut},
+    validate::{find_non_canonical, find_non_canonical_par},
+};
+
+/// Elements converted per write or read.
+const SERIALIZE_CHUNK: usize = 1 << 16;
+
+/// Serializes `x` exactly like `x.serialize_compressed(writer)`.
+pub fn serialize_compressed_bn254<W: Write>(
+    x: &[Fr],
+    mut writer: W,
+) -> Result<(), SerializationError> {
+    (x.len() as u64).serialize_compressed(&mut writer)?;
+
+    let mut buf = vec![[0u64; 4]; std::cmp::min(x.len(), SERIALIZE_CHUNK)];
+    for chunk in x.chunks(SERIALIZE_CHUNK) {
+        let buf = &mut buf[..chunk.len()];
+        if schedule::should_parallelize(VecOp::Mul, chunk.len()) {
+            montgomery_to_canonical_par_bn254(chunk, buf);
+        } else {
+            montgomery_to_canonical_bn254(chunk, buf);
+        }
+        writer.write_all(limb_bytes(buf))?;
+    }
+    Ok(())
+}
+
+/// Reads into `buf` until it is full or the reader is exhausted, returning the bytes read.
+fn read_up_to<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Deserializes a vector written by `CanonicalSerialize` (or [`serialize_compressed_bn254`]),
+/// returning the same result as `Vec::<Fr>::deserialize_compressed(reader)`, errors included.
+pub fn deserialize_compressed_bn254<R: Read>(mut reader: R) -> Result<Vec<Fr>, SerializationError> {
+    let len = u64::deserialize_compressed(&mut reader)?;
+
+    // Grow chunk by chunk so that a bogus length fails at the end of the input instead of
+    // allocating up front.
+    let mut out: Vec<Fr> = Vec::new();
+    let mut buf = vec![[0u64; 4]; std::cmp::min(len, SERIALIZE_CHUNK as u64) as usize];
+    while (out.len() as u64) < len {
+        let start = out.len();
+        let n = std::cmp::min(SERIALIZE_CHUNK as u64, len - start as u64) as usize;
+        let buf = &mut buf[..n];
+        let read = read_up_to(&mut reader, limb_bytes_mut(buf))?;
+        let (complete, rest) = buf.split_at_mut(read / 32);
+
+        let invalid = if schedule::should_parallelize(VecOp::Validate, complete.len()) {
+            schedule::install(VecOp::Validate, || {
+                let chunk_size = schedule::chunk_size(VecOp::Validate, complete.len());
+                find_non_canonical_par(complete, &constants::BN254_FR_MODULUS, chunk_size)
+            })
+        } else {
+            find_non_canonical(complete, &constants::BN254_FR_MODULUS)
+        };
+        // arkworks reads field elements without flags, so a set top bit is just out of range.
+        if invalid.is_some() {
+            return Err(SerializationError::InvalidData);
+        }
+        if complete.len() < n {
+            // The reader is exhausted, so this fails with the same error arkworks reports.
+            reader.read_exact(&mut limb_bytes_mut(rest)[read % 32..])?;
+        }
+
+        out.resize(start + n, Fr::zero());
+        if schedule::should_parallelize(VecOp::Mul, complete.len()) {
+            canonical_to_montgomery_par_bn254(complete, &mut out[start..]);
+        } else {
+            canonical_to_montgomery_bn254(complete, &mut out[start..]);
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::rand_vec;
+
+    #[test]
+    fn parity_ark_serialize() {
+        for len in [0, 3, SERIALIZE_CHUNK + 5] {
+            let x: Vec<Fr> = rand_vec(len);
+            let mut ark_bytes = Vec::new();
+            x.serialize_compressed(&mut ark_bytes).unwrap();
+
+            let mut bytes = Vec::new();
+            serialize_compressed_bn254(&x, &mut bytes).unwrap();
+            assert_eq!(bytes, ark_bytes);
+            assert_eq!(deserialize_compressed_bn254(bytes.as_slice()).unwrap(), x);
+        }
+    }
+
+    #[test]
+    fn parity_ark_deserialize_errors() {
+        let x: Vec<Fr> = rand_vec(20);
+        let mut bytes = Vec::new();
+        x.serialize_compressed(&mut bytes).unwrap();
+        let element = |i: usize| 8 + 32 * i..8 + 32 * (i + 1);
+
+        let mut at_modulus = bytes.clone();
+        at_modulus[element(11)].copy_from_slice(limb_bytes(&[constants::BN254_FR_MODULUS]));
+        let mut flagged = bytes.clone();
+        flagged[element(11).end - 1] |= 0x80;
+        let truncated = bytes[..bytes.len() - 1].to_vec();
+
+        for input in [at_modulus, flagged, truncated] {
+            let ark = Vec::<Fr>::deserialize_compressed(input.as_slice()).unwrap_err();
+            let ours = deserialize_compressed_bn254(input.as_slice()).unwrap_err();
+            assert_eq!(format!("{ours:?}"), format!("{ark:?}"));
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use ark_bn254::Fr;
+
+use crate::{
+    inner_product_bn254,
+    limbs::MUL_BLOCK,
+    mul_vec_inplace_bn254,
+    par::*,
+    schedule::{self, VecOp},
+};
+
+/// Sparse matrix over Fr in compressed sparse row (CSR) form.
+///
+/// Row `i` owns the entries `row_ptr[i]..row_ptr[i + 1]` of `col_idx` and `values`,
+/// with columns sorted in increasing order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsrMatrix {
+    num_rows: usize,
+    num_cols: usize,
+    row_ptr: Vec<usize>,
+    col_idx: Vec<usize>,
+    values: Vec<Fr>,
+}
+
+impl CsrMatrix {
+    /// Builds a matrix from `(row, col, value)` entries in any order.
+    /// Entries sharing the same position are summed.
+    pub fn from_triplets(
+        num_rows: usize,
+        num_cols: usize,
+        mut entries: Vec<(usize, usize, Fr)>,
+    ) -> Self {
+        entries.sort_unstable_by_key(|&(row, col, _)| (row, col));
+
+        let mut row_ptr = vec![0; num_rows + 1];
+        let mut col_idx: Vec<usize> = Vec::with_capacity(entries.len());
+        let mut values: Vec<Fr> = Vec::with_capacity(entries.len());
+        let mut last: Option<(usize, usize)> = None;
+
+        for (row, col, value) in entries {
+            assert!(
+                row < num_rows,
+                "row {row} out of bounds for {num_rows} rows"
+            );
+            assert!(
+                col < num_cols,
+                "col {col} out of bounds for {num_cols} cols"
+            );
+
+            if last == Some((row, col)) {
+                *values.last_mut().unwrap() += value;
+                continue;
+            }
+            last = Some((row, col));
+            row_ptr[row + 1] += 1;
+            col_idx.push(col);
+            values.push(value);
+        }
+
+        for i in 0..num_rows {
+            row_ptr[i + 1] += row_ptr[i];
+        }
+
+        Self {
+            num_rows,
+            num_cols,
+            row_ptr,
+            col_idx,
+            values,
+        }
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.num_cols
+    }
+
+    /// Number of stored entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn row_ptr(&self) -> &[usize] {
+        &self.row_ptr
+    }
+
+    pub fn col_idx(&self) -> &[usize] {
+        &self.col_idx
+    }
+
+    pub fn values(&self) -> &[Fr] {
+        &self.values
+    }
+
+    /// Column indices and values of row `i`.
+    pub fn row(&self, i: usize) -> (&[usize], &[Fr]) {
+        let range = self.row_ptr[i]..self.row_ptr[i + 1];
+        (&self.col_idx[range.clone()], &self.values[range])
+    }
+}
+
+/// Matrix entries multiplied per kernel call by [`spmv_rows`].
+const SPMV_BATCH: usize = 1024;
+
+/// Row length from which [`spmv_rows`] reduces a row through the inner product kernel on its own.
+const SPMV_LONG_ROW: usize = 2 * MUL_BLOCK;
+
+/// Computes rows `row_start..row_start + out.len()` of `m * z`.
+///
+/// R1CS rows hold a handful of entries, too few for the AVX-512 loop of the mul kernel, so
+/// consecutive short rows are batched up to [`SPMV_BATCH`] entries: their `z[col]` operands are
+/// gathered into `scratch`, multiplied by the values in one call, and the products are summed row
+/// by row. Rows of at least [`SPMV_LONG_ROW`] entries go through the inner product kernel instead.
+fn spmv_rows(m: &CsrMatrix, z: &[Fr], row_start: usize, out: &mut [Fr], scratch: &mut Vec<Fr>) {
+    let row_ptr = &m.row_ptr[row_start..=row_start + out.len()];
+    let row_len = |r: usize| row_ptr[r + 1] - row_ptr[r];
+
+    let mut i = 0;
+    while i < out.len() {
+        let first = row_ptr[i];
+        if row_len(i) >= SPMV_LONG_ROW {
+            let entries = first..row_ptr[i + 1];
+            scratch.clear();
+            scratch.extend(m.col_idx[entries.clone()].iter().map(|&col| z[col]));
+            out[i] = inner_product_bn254(&m.values[entries], scratch);
+            i += 1;
+            continue;
+        }
+
+        let mut end = i + 1;
+        while end < out.len()
+            && row_len(end) < SPMV_LONG_ROW
+            && row_ptr[end + 1] - first <= SPMV_BATCH
+        {
+            end += 1;
+        }
+        let entries = first..row_ptr[end];
+
+        scratch.clear();
+        scratch.extend(m.col_idx[entries.clone()].iter().map(|&col| z[col]));
+        mul_vec_inplace_bn254(scratch, &m.values[entries]);
+        for (out_r, bounds) in out[i..end].iter_mut().zip(row_ptr[i..=end].windows(2)) {
+            *out_r = scratch[bounds[0] - first..bounds[1] - first].iter().sum();
+        }
+        i = end;
+    }
+}
+
+pub fn spmv_bn254(m: &CsrMatrix, z: &[Fr], out: &mut [Fr]) {
+    assert_eq!(z.len(), m.num_cols);
+    assert_eq!(out.len(), m.num_rows);
+
+    let mut scratch = Vec::new();
+    spmv_rows(m, z, 0, out, &mut scratch);
+}
+
+pub fn spmv_par_bn254(m: &CsrMatrix, z: &[Fr], out: &mut [Fr]) {
+    assert_eq!(z.len(), m.num_cols);
+    assert_eq!(out.len(), m.num_rows);
+
+    schedule::install(VecOp::Spmv, || {
+        let chunk_size = schedule::chunk_size(VecOp::Spmv, out.len());
+
+        out.par_chunks_mut(chunk_size).enumerate().for_each_init(
+            Vec::new,
+            |scratch, (chunk, out_chunk)| {
+                spmv_rows(m, z, chunk * chunk_size, out_chunk, scratch);
+            },
+        );
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::rand_vec;
+    use ark_std::Zero;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn rand_matrix(
+        num_rows: usize,
+        num_cols: usize,
+        nnz: usize,
+    ) -> (CsrMatrix, Vec<(usize, usize, Fr)>) {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let values: Vec<Fr> = rand_vec(nnz);
+        let entries: Vec<(usize, usize, Fr)> = values
+            .into_iter()
+            .map(|v| (rng.gen_range(0..num_rows), rng.gen_range(0..num_cols), v))
+            .collect();
+        (
+            CsrMatrix::from_triplets(num_rows, num_cols, entries.clone()),
+            entries,
+        )
+    }
+
+    #[test]
+    fn parity_ark_spmv() {
+        // Short rows batched together, some empty, mixed with long rows, and rows longer than a batch.
+        for (num_rows, num_cols, nnz) in [(1_000, 700, 5_000), (100, 500, 2_500), (3, 5_000, 4_000)]
+        {
+            let (m, entries) = rand_matrix(num_rows, num_cols, nnz);
+            let z: Vec<Fr> = rand_vec(num_cols);
+
+            let mut ark_out = vec![Fr::zero(); num_rows];
+            for &(row, col, v) in &entries {
+                ark_out[row] += v * z[col];
+            }
+
+            let mut out = vec![Fr::zero(); num_rows];
+            spmv_bn254(&m, &z, &mut out);
+            assert_eq!(out, ark_out);
+        }
+    }
+
+    #[test]
+    fn parallel_parity_spmv() {
+        const NUM_ROWS: usize = 10_000;
+        const NUM_COLS: usize = 10_000;
+        let (m, _) = rand_matrix(NUM_ROWS, NUM_COLS, 50_000);
+        let z: Vec<Fr> = rand_vec(NUM_COLS);
+
+        let mut out = vec![Fr::zero(); NUM_ROWS];
+        let mut out_par = vec![Fr::zero(); NUM_ROWS];
+        spmv_bn254(&m, &z, &mut out);
+        spmv_par_bn254(&m, &z, &mut out_par);
+        assert_eq!(out, out_par);
+    }
+}
@@ -1,11 +1,208 @@
+#[cfg(any(test, feature = "parallel"))]
 use ark_ff::PrimeField;
+#[cfg(any(test, feature = "parallel"))]
 use rand::SeedableRng;
+#[cfg(any(test, feature = "parallel"))]
 use rand_chacha::ChaCha8Rng;
-use rayon::prelude::*;
 
+use ark_bls12_381::Fq as Fq381;
+use ark_bn254::{Fq, Fr};
+use ark_ff::BigInt;
+
+use crate::field::VectorizedField;
+#[cfg(any(test, feature = "parallel"))]
+use crate::par::*;
+
+// The limb views below cast `&[Fr]` / `&[Fq]` to `&[[u64; 4]]` (and BLS12-381 `Fq` to
+// `&[[u64; 6]]`), which holds only while `Fp<_, N>` is its `BigInt<N>` of `N` little-endian words
+// and nothing else. arkworks does not promise
+// that layout, so it is checked for whichever release the `ark-0_*` feature selects.
+const _: () = {
+    use std::mem::{align_of, size_of};
+    assert!(size_of::<BigInt<4>>() == size_of::<[u64; 4]>());
+    assert!(align_of::<BigInt<4>>() == align_of::<[u64; 4]>());
+    assert!(size_of::<Fr>() == size_of::<[u64; 4]>() && align_of::<Fr>() == align_of::<[u64; 4]>());
+    assert!(size_of::<Fq>() == size_of::<[u64; 4]>() && align_of::<Fq>() == align_of::<[u64; 4]>());
+    assert!(size_of::<BigInt<6>>() == size_of::<[u64; 6]>());
+    assert!(
+        size_of::<Fq381>() == size_of::<[u64; 6]>()
+            && align_of::<Fq381>() == align_of::<[u64; 6]>()
+    );
+};
+
+/// Random elements for the tests and benchmarks.
+#[cfg(any(test, feature = "parallel"))]
 pub fn rand_vec<F: PrimeField>(size: usize) -> Vec<F> {
     (0..size)
         .into_par_iter()
-        .map_init(|| ChaCha8Rng::from_entropy(), |rng, _| F::rand(rng))
+        .map_init(ChaCha8Rng::from_entropy, |rng, _| F::rand(rng))
         .collect()
 }
+
+#[inline]
+fn assert_limbs<F: VectorizedField, const N: usize>() {
+    assert!(F::LIMBS == N && std::mem::size_of::<F>() == std::mem::size_of::<[u64; N]>());
+}
+
+/// Views elements as their raw `N`-limb words, in whatever representation they hold.
+pub(crate) fn limbs<F: VectorizedField, const N: usize>(x: &[F]) -> &[[u64; N]] {
+    assert_limbs::<F, N>();
+    unsafe { std::slice::from_raw_parts(x.as_ptr() as *const [u64; N], x.len()) }
+}
+
+pub(crate) fn limbs_mut<F: VectorizedField, const N: usize>(x: &mut [F]) -> &mut [[u64; N]] {
+    assert_limbs::<F, N>();
+    unsafe { std::slice::from_raw_parts_mut(x.as_mut_ptr() as *mut [u64; N], x.len()) }
+}
+
+/// The element whose raw limbs are `x`, the inverse of [`limbs`].
+pub(crate) fn from_limbs<F: VectorizedField, const N: usize>(x: [u64; N]) -> F {
+    assert_limbs::<F, N>();
+    unsafe { std::mem::transmute_copy(&x) }
+}
+
+pub(crate) fn limb_bytes(x: &[[u64; 4]]) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(x.as_ptr() as *const u8, std::mem::size_of_val(x)) }
+}
+
+pub(crate) fn limb_bytes_mut(x: &mut [[u64; 4]]) -> &mut [u8] {
+    unsafe { std::slice::from_raw_parts_mut(x.as_mut_ptr() as *mut u8, std::mem::size_of_val(x)) }
+}
+
+/// Defines the vector functions of a field whose kernels are the `$kernels` operations (the
+/// [`crate::limbs`] or [`crate::limbs384`] module in scope) run against `$modulus`, named in the order
+/// `VectorizedField` declares them, and a `$test` checking every one of them against arkworks.
+macro_rules! field_functions {
+    (
+        $field:ty, $kernels:ident, $modulus:expr, $test:ident;
+        [
+            $add_vec:ident, $add_vec_par:ident, $add_vec_inplace:ident, $add_vec_inplace_par:ident,
+            $sub_vec:ident, $sub_vec_par:ident, $sub_vec_inplace:ident, $sub_vec_inplace_par:ident,
+            $mul_vec:ident, $mul_vec_par:ident, $mul_vec_inplace:ident, $mul_vec_inplace_par:ident,
+            $sum_vec:ident, $sum_vec_par:ident, $inner_product:ident, $inner_product_par:ident $(,)?
+        ]
+    ) => {
+        field_functions!(@binary $field, $kernels, $modulus; $add_vec: add, $add_vec_par: add_par);
+        field_functions!(@inplace $field, $kernels, $modulus;
+            $add_vec_inplace: add_inplace, $add_vec_inplace_par: add_inplace_par);
+        field_functions!(@binary $field, $kernels, $modulus; $sub_vec: sub, $sub_vec_par: sub_par);
+        field_functions!(@inplace $field, $kernels, $modulus;
+            $sub_vec_inplace: sub_inplace, $sub_vec_inplace_par: sub_inplace_par);
+        field_functions!(@binary $field, $kernels, $modulus; $mul_vec: mul, $mul_vec_par: mul_par);
+        field_functions!(@inplace $field, $kernels, $modulus;
+            $mul_vec_inplace: mul_inplace, $mul_vec_inplace_par: mul_inplace_par);
+
+        pub fn $sum_vec(x: &[$field]) -> $field {
+            $crate::utils::from_limbs($kernels::sum($modulus, $crate::utils::limbs(x)))
+        }
+
+        pub fn $sum_vec_par(x: &[$field]) -> $field {
+            $crate::utils::from_limbs($kernels::sum_par($modulus, $crate::utils::limbs(x)))
+        }
+
+        pub fn $inner_product(x: &[$field], y: &[$field]) -> $field {
+            $crate::utils::from_limbs($kernels::inner_product(
+                $modulus,
+                $crate::utils::limbs(x),
+                $crate::utils::limbs(y),
+            ))
+        }
+
+        pub fn $inner_product_par(x: &[$field], y: &[$field]) -> $field {
+            $crate::utils::from_limbs($kernels::inner_product_par(
+                $modulus,
+                $crate::utils::limbs(x),
+                $crate::utils::limbs(y),
+            ))
+        }
+
+        #[cfg(test)]
+        #[test]
+        fn $test() {
+            use $crate::ark::*;
+            use ark_std::{One, Zero};
+
+            type Binary = fn(&[$field], &[$field], &mut [$field]);
+            type Inplace = fn(&mut [$field], &[$field]);
+            let ops: [(Binary, [Binary; 2], [Inplace; 2]); 3] = [
+                (ark_batch_add, [$add_vec, $add_vec_par], [$add_vec_inplace, $add_vec_inplace_par]),
+                (ark_batch_sub, [$sub_vec, $sub_vec_par], [$sub_vec_inplace, $sub_vec_inplace_par]),
+                (ark_batch_mul, [$mul_vec, $mul_vec_par], [$mul_vec_inplace, $mul_vec_inplace_par]),
+            ];
+
+            for len in [0, 1, 7, 16, 17, 1_003] {
+                let mut x: Vec<$field> = $crate::utils::rand_vec(len);
+                let mut y: Vec<$field> = $crate::utils::rand_vec(len);
+                // The largest residue exercises every carry and the final subtractions.
+                if len > 0 {
+                    x[0] = -<$field>::one();
+                    y[0] = -<$field>::one();
+                }
+
+                let mut ark_z = vec![<$field>::zero(); len];
+                for (ark_op, binary, inplace) in ops {
+                    ark_op(&x, &y, &mut ark_z);
+                    for op in binary {
+                        let mut z = vec![<$field>::zero(); len];
+                        op(&x, &y, &mut z);
+                        assert_eq!(z, ark_z);
+                    }
+                    for op in inplace {
+                        let mut w = x.clone();
+                        op(&mut w, &y);
+                        assert_eq!(w, ark_z);
+                    }
+                }
+
+                for sum in [$sum_vec, $sum_vec_par] {
+                    assert_eq!(sum(&x), ark_batch_sum(&x));
+                }
+                for inner_product in [$inner_product, $inner_product_par] {
+                    assert_eq!(inner_product(&x, &y), ark_inner_product(&x, &y));
+                }
+            }
+        }
+    };
+    (@binary $field:ty, $kernels:ident, $modulus:expr; $($name:ident: $kernel:ident),*) => {$(
+        pub fn $name(x: &[$field], y: &[$field], z: &mut [$field]) {
+            $kernels::$kernel(
+                $modulus,
+                $crate::utils::limbs(x),
+                $crate::utils::limbs(y),
+                $crate::utils::limbs_mut(z),
+            )
+        }
+    )*};
+    (@inplace $field:ty, $kernels:ident, $modulus:expr; $($name:ident: $kernel:ident),*) => {$(
+        pub fn $name(x: &mut [$field], y: &[$field]) {
+            $kernels::$kernel($modulus, $crate::utils::limbs_mut(x), $crate::utils::limbs(y))
+        }
+    )*};
+}
+pub(crate) use field_functions;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limbs_are_ark_montgomery_words() {
+        let x: Vec<Fr> = rand_vec(9);
+        for (xi, li) in x.iter().zip(limbs(&x)) {
+            assert_eq!(xi.0 .0, *li);
+            assert_eq!(from_limbs::<Fr, 4>(*li), *xi);
+        }
+
+        let x: Vec<Fq> = rand_vec(9);
+        for (xi, li) in x.iter().zip(limbs(&x)) {
+            assert_eq!(xi.0 .0, *li);
+            assert_eq!(from_limbs::<Fq, 4>(*li), *xi);
+        }
+
+        let x: Vec<Fq381> = rand_vec(9);
+        for (xi, li) in x.iter().zip(limbs(&x)) {
+            assert_eq!(xi.0 .0, *li);
+            assert_eq!(from_limbs::<Fq381, 6>(*li), *xi);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Range checks on raw 256-bit limbs.
+//!
+//! The assembly kernels assume canonical residues, `x < p`, and return wrong results for
+//! anything larger without reporting an error.
+
+use core::arch::x86_64::*;
+
+use crate::par::*;
+
+/// Elements compared per AVX-512 iteration, as four ZMM loads of two elements each.
+const LANES: usize = 8;
+
+fn below<const N: usize>(x: &[u64; N], m: &[u64; N]) -> bool {
+    x.iter().rev().lt(m.iter().rev())
+}
+
+/// Index of the first element of `x` that is not below the modulus `m`.
+pub(crate) fn find_non_canonical(x: &[[u64; 4]], m: &[u64; 4]) -> Option<usize> {
+    let blocks = x.len() / LANES * LANES;
+    // Safety: the crate only builds for AVX-512 hosts (see build.rs).
+    unsafe { find_non_canonical_avx512(&x[..blocks], m) }.or_else(|| {
+        x[blocks..]
+            .iter()
+            .position(|xi| !below(xi, m))
+            .map(|i| blocks + i)
+    })
+}
+
+/// [`find_non_canonical`] split across rayon in `chunk_size` pieces.
+pub(crate) fn find_non_canonical_par(
+    x: &[[u64; 4]],
+    m: &[u64; 4],
+    chunk_size: usize,
+) -> Option<usize> {
+    x.par_chunks(chunk_size)
+        .enumerate()
+        .find_map_first(|(i, chunk)| find_non_canonical(chunk, m).map(|j| i * chunk_size + j))
+}
+
+/// In debug builds, panics unless every element of `x` is below the modulus `m`.
+#[inline]
+#[track_caller]
+pub(crate) fn debug_assert_below(x: &[[u64; 4]], m: &[u64; 4]) {
+    if cfg!(debug_assertions) {
+        if let Some(i) = find_non_canonical(x, m) {
+            panic!(
+                "kernel input {i} of {} is not a canonical residue: {:x?} >= modulus",
+                x.len(),
+                x[i]
+            );
+        }
+    }
+}
+
+/// [`debug_assert_below`] for elements of any width, checked one element at a time.
+#[inline]
+#[track_caller]
+pub(crate) fn debug_assert_below_scalar<const N: usize>(x: &[[u64; N]], m: &[u64; N]) {
+    if cfg!(debug_assertions) {
+        if let Some(i) = x.iter().position(|xi| !below(xi, m)) {
+            panic!(
+                "kernel input {i} of {} is not a canonical residue: {:x?} >= modulus",
+                x.len(),
+                x[i]
+            );
+        }
+    }
+}
+
+#[target_feature(enable = "avx512f")]
+unsafe fn find_non_canonical_avx512(x: &[[u64; 4]], m: &[u64; 4]) -> Option<usize> {
+    let modulus = _mm512_set_epi64(
+        m[3] as i64,
+        m[2] as i64,
+        m[1] as i64,
+        m[0] as i64,
+        m[3] as i64,
+        m[2] as i64,
+        m[1] as i64,
+        m[0] as i64,
+    );
+
+    for (i, block) in x.chunks_exact(LANES).enumerate() {
+        let ptr = block.as_ptr() as *const __m512i;
+        // Bit k is set if element k of the block is >= m.
+        let mut ge = 0u8;
+        for j in 0..LANES / 2 {
+            let v = _mm512_loadu_si512(ptr.add(j));
+            let gt = _mm512_cmpgt_epu64_mask(v, modulus);
+            let lt = _mm512_cmplt_epu64_mask(v, modulus);
+            // Each nibble holds one element, limb 0 in the low bit. The most significant
+            // differing limb decides, so x >= m exactly when its `gt` nibble is at least its
+            // `lt` nibble (equal only when both are zero).
+            for k in 0..2 {
+                let (g, l) = ((gt >> (4 * k)) & 0xf, (lt >> (4 * k)) & 0xf);
+                ge |= ((g >= l) as u8) << (2 * j + k);
+            }
+        }
+        if ge != 0 {
+            return Some(i * LANES + ge.trailing_zeros() as usize);
+        }
+    }
+    None
+}
+
+#[cfg(all(test, feature = "ark"))]
+mod tests {
+    use crate::{
+        constants, utils::rand_vec, validate_canonical_bn254, validate_canonical_par_bn254,
+    };
+    use ark_bn254::Fr;
+    use ark_ff::BigInt;
+
+    fn raw(limbs: [u64; 4]) -> Fr {
+        Fr::new_unchecked(BigInt(limbs))
+    }
+
+    #[test]
+    fn validate_canonical_rejects_out_of_range() {
+        let p = constants::BN254_FR_MODULUS;
+        let mut p_minus_one = p;
+        p_minus_one[0] -= 1;
+        let mut p_plus_one = p;
+        p_plus_one[0] += 1;
+        let mut high_limb = [0; 4];
+        high_limb[3] = p[3] + 1;
+        let bad = [p, p_plus_one, high_limb, [u64::MAX; 4], [0, 0, 0, 1 << 63]];
+
+        // Positions inside the AVX-512 blocks and in the scalar tail.
+        let mut x: Vec<Fr> = rand_vec(43);
+        x[7] = raw(p_minus_one);
+        assert_eq!(validate_canonical_bn254(&x), Ok(()));
+        assert_eq!(validate_canonical_par_bn254(&x), Ok(()));
+        for limbs in bad {
+            for i in [0, 5, 17, 40, 42] {
+                let mut y = x.clone();
+                y[i] = raw(limbs);
+                y[42.min(i + 1)] = raw(limbs);
+                assert_eq!(validate_canonical_bn254(&y), Err(i));
+                assert_eq!(validate_canonical_par_bn254(&y), Err(i));
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not a canonical residue")]
+    fn debug_kernels_reject_out_of_range() {
+        let x: Vec<Fr> = rand_vec(20);
+        let mut y: Vec<Fr> = rand_vec(20);
+        y[13] = raw(constants::BN254_FR_MODULUS);
+        crate::add_vec_par_bn254(&x, &y, &mut vec![Fr::from(0u64); 20]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn debug_kernels_check_every_input() {
+        let x: Vec<Fr> = rand_vec(20);
+        let mut bad = x.clone();
+        bad[3] = raw([u64::MAX; 4]);
+        let mut z = x.clone();
+        let panics =
+            |f: &dyn Fn()| std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).is_err();
+
+        assert!(panics(&|| crate::mul_vec_bn254(&x, &bad, &mut z.clone())));
+        assert!(panics(&|| crate::sub_vec_nt_bn254(
+            &bad,
+            &x,
+            &mut z.clone()
+        )));
+        assert!(panics(&|| {
+            crate::sum_vec_bn254(&bad);
+        }));
+        assert!(panics(&|| {
+            crate::inner_product_par_bn254(&x, &bad);
+        }));
+        assert!(panics(&|| {
+            crate::poly_eval_bn254(&bad, x[0]);
+        }));
+        assert!(panics(&|| {
+            crate::canonical_to_montgomery_bn254(&[[u64::MAX; 4]], &mut z.clone()[..1]);
+        }));
+        crate::mul_vec_bn254(&x, &x, &mut z);
+    }
+}
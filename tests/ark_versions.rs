@@ -0,0 +1,35 @@
+//! Runs the library's arkworks parity tests (`parity_ark_*`, `parallel_parity_*`) against the
+//! arkworks release this build did not select, so `cargo test` covers both `ark-0_4` and
+//! `ark-0_5`. Uses a separate target directory, like `tests/no_std.rs`.
+
+#![cfg(any(feature = "ark-0_4", feature = "ark-0_5"))]
+
+use std::{env, path::PathBuf, process::Command};
+
+#[cfg(feature = "ark-0_4")]
+const OTHER: &str = "ark-0_5";
+#[cfg(feature = "ark-0_5")]
+const OTHER: &str = "ark-0_4";
+
+#[test]
+fn parity_other_ark_release() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(OTHER);
+
+    let output = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+        .args(["test", "--lib", "--no-default-features"])
+        .args(["--features", &format!("std,parallel,{OTHER}")])
+        .arg("--manifest-path")
+        .arg(manifest_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .args(["--", "parity"])
+        .output()
+        .expect("failed to run cargo");
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
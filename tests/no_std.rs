@@ -0,0 +1,48 @@
+//! Builds the library with `--no-default-features`, where it is `#![no_std]`, the way an
+//! embedding crate without `std` would, in a separate target directory.
+//!
+//! The build targets `x86_64-unknown-none`, which has no `std` to fall back on, when its standard
+//! library is installed (`rustup target add x86_64-unknown-none`), and the host otherwise.
+
+use std::{env, path::PathBuf, process::Command};
+
+const NO_STD_TARGET: &str = "x86_64-unknown-none";
+
+fn rustc_sysroot() -> Option<PathBuf> {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let output = Command::new(rustc)
+        .args(["--print", "sysroot"])
+        .output()
+        .ok()?;
+    Some(PathBuf::from(String::from_utf8(output.stdout).ok()?.trim()))
+}
+
+#[test]
+fn builds_without_std() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("no_std");
+
+    let mut cargo = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()));
+    cargo
+        .args(["build", "--lib", "--no-default-features"])
+        .arg("--manifest-path")
+        .arg(manifest_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .env("RUSTFLAGS", "-D warnings");
+
+    let has_no_std_target = rustc_sysroot()
+        .is_some_and(|sysroot| sysroot.join("lib/rustlib").join(NO_STD_TARGET).is_dir());
+    if has_no_std_target {
+        cargo.args(["--target", NO_STD_TARGET]);
+    } else {
+        eprintln!("{NO_STD_TARGET} is not installed, building the no_std library for the host");
+    }
+
+    let output = cargo.output().expect("failed to run cargo");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}